@@ -0,0 +1,63 @@
+//! 投票承诺的计算
+//!
+//! V2 方案对各字段做长度前缀编码，并绑定链 ID、合约地址与轮次，避免分隔符歧义以及跨轮次、
+//! 跨合约实例的重放；Legacy 为 0.1.0 的 `sha256(addr|reveal|salt)`，由 `SetCommitmentScheme` 切换。
+//! 委托揭示与批量承诺的签名消息使用相同编码与独立的 domain。
+use cosmwasm_std::Env;
+use sha2::{Digest, Sha256};
+
+use crate::state::{CommitmentScheme, Config};
+
+/// V2 方案的域分隔标签
+pub const COMMITMENT_DOMAIN: &str = "dd_blind_box:commitment:v2";
+/// 委托揭示签名消息的域分隔标签
+pub const REVEAL_DOMAIN: &str = "dd_blind_box:reveal:v1";
+/// 批量承诺签名消息的域分隔标签
+pub const COMMIT_DOMAIN: &str = "dd_blind_box:commit:v1";
+
+/// V2 承诺（hex）：依次哈希 domain、chain_id、contract、round（8 字节大端）、voter、reveal、salt，
+/// 字符串字段前加 4 字节大端长度
+pub fn commitment_v2(chain_id: &str, contract: &str, round: u64, voter: &str, reveal: &str, salt: &str) -> String {
+    hex::encode(hash_fields(COMMITMENT_DOMAIN, chain_id, contract, round, &[voter, reveal, salt]))
+}
+
+/// 委托揭示时用户签名的 32 字节摘要（编码同 V2 承诺，domain 为 REVEAL_DOMAIN）；
+/// secp256k1 与 ed25519 均直接对该摘要签名
+pub fn reveal_sign_digest(chain_id: &str, contract: &str, round: u64, voter: &str, reveal: &str, salt: &str) -> [u8; 32] {
+    hash_fields(REVEAL_DOMAIN, chain_id, contract, round, &[voter, reveal, salt])
+}
+
+/// 批量承诺时用户签名的 32 字节摘要（domain 为 COMMIT_DOMAIN，字段为 voter 与 commitment）
+pub fn commit_sign_digest(chain_id: &str, contract: &str, round: u64, voter: &str, commitment: &str) -> [u8; 32] {
+    hash_fields(COMMIT_DOMAIN, chain_id, contract, round, &[voter, commitment])
+}
+
+/// 旧格式承诺（hex）：sha256(voter|reveal|salt)
+pub fn commitment_legacy(voter: &str, reveal: &str, salt: &str) -> String {
+    hex::encode(Sha256::digest(format!("{}|{}|{}", voter, reveal, salt).as_bytes()))
+}
+
+/// 按配置的方案与当前轮次计算承诺
+pub fn compute_commitment(cfg: &Config, env: &Env, voter: &str, reveal: &str, salt: &str) -> String {
+    match cfg.commitment_scheme {
+        CommitmentScheme::Legacy => commitment_legacy(voter, reveal, salt),
+        CommitmentScheme::V2 => commitment_v2(&env.block.chain_id, env.contract.address.as_str(), cfg.round, voter, reveal, salt),
+    }
+}
+
+fn hash_fields(domain: &str, chain_id: &str, contract: &str, round: u64, fields: &[&str]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for field in [domain, chain_id, contract] {
+        put_field(&mut hasher, field);
+    }
+    hasher.update(round.to_be_bytes());
+    for field in fields {
+        put_field(&mut hasher, field);
+    }
+    hasher.finalize().into()
+}
+
+fn put_field(hasher: &mut Sha256, field: &str) {
+    hasher.update((field.len() as u32).to_be_bytes());
+    hasher.update(field.as_bytes());
+}
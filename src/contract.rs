@@ -1,12 +1,35 @@
-use cosmwasm_std::{attr, to_json_binary, BankMsg, Coin, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128, SubMsgResponse};
+use cosmwasm_std::{attr, to_json_binary, BankMsg, Coin, Deps, DepsMut, Env, MessageInfo, Response, Reply, StdResult, Uint128};
 use sha2::Digest;
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
+use cw20::Cw20ReceiveMsg;
 
+use crate::commitment;
 use crate::error::ContractError;
-use crate::msg::{ApprovalResponse, ConfigResponse, DepositResponse, ExecuteMsg, InstantiateMsg, IsApprovedForAllResponse, MigrateMsg, NftInfoResponse, OwnerOfResponse, QueryMsg, TierListResponse, TierResponse};
-use crate::state::{CommitInfo, Config, Payout, PhaseWindow, RevealInfo, Scale, VoteState, COMMITS, CONFIG, DEPOSITS, REVEALS, TIERS};
+use crate::events;
+use crate::migrations;
+use crate::msg::{BatchEntryResult, BatchResponse, SignedCommit, SignedReveal, RevealDelegateMsg, RevealDelegateResponse, ComputeCommitmentResponse, OptionTally, VoteResultsResponse, NftMinterResponse, AllCommitsResponse, AllDepositsResponse, AllRevealsResponse, CommitEntry, DepositEntry, RevealEntry, TopDepositorsResponse, ApprovalResponse, ConfigResponse, Cw20HookMsg, DepositResponse, ExecuteMsg, InstantiateMsg, IsApprovedForAllResponse, MigrateMsg, NftInfoResponse, CurrentPriceResponse, OwnerOfResponse, PreviewMintResponse, PrizesResponse, QueryMsg, QuoteResponse, RevealStatusResponse, RevealedItemResponse, RevenueShareMsg, SaleStatusResponse, StatusResponse, TokenRange, UserInfoResponse, WindowStatus, TreasuryBalance, TreasuryResponse, TierCountsResponse, TierListResponse, TierResponse};
+use crate::state::{load_config, open_principal, rebase_principal, save_deposit, save_tier, BulkDiscount, CommitInfo, CommitmentScheme, RevealDelegate, Config, HiddenItem, MintTemplate, Payout, PriceCurve, Pricing, PhaseWindow, PrizeInfo, ProtocolFee, RevealInfo, RevealPlan, RevenueShare, SaleMode, Scale, TierPrize, VoteState, BALLOT, BALLOT_VOTES, COMMITS, CONFIG, DEPOSITS, DEPOSIT_RANK, EXTRA_DEPOSITS, FEES_COLLECTED, MAX_PROTOCOL_FEE_BPS, LAST_CLEARING_PRICE, NEXT_PRIZE_TOKEN_ID, PRIZES, PRIZE_TOKEN_ID_BASE, PURCHASES, REVEALED_ITEMS, REVEALS, REVEAL_DELEGATES, REVEAL_PLAN, MAX_TOTAL_SUPPLY, PAYOUTS, RESOLVED_PRINCIPAL, SETTLED, SETTLED_ROUND, SETTLEMENT_EPOCH, STORAGE_VERSION, TIERS, TIER_COUNTS, TIER_INDEX, TIER_PRIZES, TOTAL_PRINCIPAL, VOTER_COUNTS, VOTE_TALLY, MAX_BALLOT_OPTIONS, WITHDRAWN};
 // use dd_algorithms_lib::{get_k_dd_rand_num, get_k_dd_rand_num_with_whitelist};
 
+/// 基点分母（10000 = 100%）
+const BPS_DENOMINATOR: u128 = 10_000;
+
+/// 结算时的最高返还倍数（一等奖 2x），用于结算前预留应付款
+const MAX_PAYOUT_MULTIPLIER: u128 = 2;
+
+/// `InstantiateNftContract` 子消息的回调 ID
+const INSTANTIATE_NFT_REPLY_ID: u64 = 1;
+
+/// `BatchCommit` / `BatchReveal` 单批最多条目数
+const MAX_BATCH_ENTRIES: usize = 100;
+
+/// 收益分账最多接收方数量
+const MAX_REVENUE_SHARES: usize = 10;
+
+/// 列表查询的默认与最大分页大小
+const DEFAULT_PAGE_LIMIT: u32 = 30;
+const MAX_PAGE_LIMIT: u32 = 100;
+
 /// 合约名称与版本（用于迁移安全校验）
 const CONTRACT_NAME: &str = "crates.io:dd_blind_box";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -15,7 +38,10 @@ const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 pub fn instantiate(deps: DepsMut, _env: Env, info: MessageInfo, msg: InstantiateMsg) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
-    let total_supply: u64 = msg.scale.total_supply();
+    let total_supply: u64 = msg.total_supply.unwrap_or_else(|| msg.scale.total_supply());
+    if total_supply == 0 || total_supply > MAX_TOTAL_SUPPLY {
+        return Err(ContractError::InvalidTotalSupply { total_supply, min: 1, max: MAX_TOTAL_SUPPLY });
+    }
     let first_prize_count = msg.first_prize_count.unwrap_or_else(|| msg.scale.default_first_prize_count());
 
     let config = Config {
@@ -30,15 +56,27 @@ pub fn instantiate(deps: DepsMut, _env: Env, info: MessageInfo, msg: Instantiate
         commit_window: PhaseWindow { start_height: None, end_height: None, start_time: None, end_time: None },
         reveal_window: PhaseWindow { start_height: None, end_height: None, start_time: None, end_time: None },
         closed_window: PhaseWindow { start_height: None, end_height: None, start_time: None, end_time: None },
+        sale_window: PhaseWindow::default(),
+        revenue_split: vec![],
+        protocol_fee: ProtocolFee::default(),
+        commitment_scheme: CommitmentScheme::V2,
+        round: 0,
         nft_contract: None,  // 初始时未设置NFT合约
         nft_code_id: None,   // 初始时未设置NFT合约代码ID
+        mint_template: MintTemplate::default(),
+        pricing: Pricing::default(),
+        cw20_token: None,  // 默认使用原生币
+        sale_mode: SaleMode::Fixed,
     };
     CONFIG.save(deps.storage, &config)?;
+    // 新部署直接使用最新存储结构，无需执行迁移步骤
+    STORAGE_VERSION.save(deps.storage, &migrations::latest_storage_version())?;
 
     Ok(Response::new().add_attributes(vec![
         attr("action", "instantiate"),
         attr("owner", info.sender),
         attr("scale", format_state_scale(&msg.scale)),
+        attr("total_supply", total_supply.to_string()),
         attr("base_denom", msg.base.denom),
         attr("base_amount", msg.base.amount),
     ]))
@@ -48,15 +86,36 @@ pub fn instantiate(deps: DepsMut, _env: Env, info: MessageInfo, msg: Instantiate
 pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::SetBase { base } => exec_set_base(deps, info, base),
+        ExecuteMsg::SetSaleMode { mode } => exec_set_sale_mode(deps, info, mode),
+        ExecuteMsg::SetRevenueSplit { shares } => exec_set_revenue_split(deps, info, shares),
+        ExecuteMsg::WithdrawProceeds { amount, recipient } => exec_withdraw_proceeds(deps, env, info, amount, recipient),
+        ExecuteMsg::SetProtocolFee { fee_bps, collector, on_payouts } => exec_set_protocol_fee(deps, info, fee_bps, collector, on_payouts),
+        ExecuteMsg::SetCommitmentScheme { scheme } => exec_set_commitment_scheme(deps, info, scheme),
+        ExecuteMsg::SetBallot { options } => exec_set_ballot(deps, info, options),
+        ExecuteMsg::SetRevealDelegate { delegate } => exec_set_reveal_delegate(deps, info, delegate),
+        ExecuteMsg::RevealFor { voter, reveal, salt, signature } => exec_reveal_for(deps, env, info, voter, reveal, salt, signature),
+        ExecuteMsg::BatchCommit { entries } => exec_batch_commit(deps, env, info, entries),
+        ExecuteMsg::BatchReveal { entries } => exec_batch_reveal(deps, env, info, entries),
+        ExecuteMsg::SetPricing { extra_prices, bulk_discounts, curve } => exec_set_pricing(deps, info, extra_prices, bulk_discounts, curve),
         ExecuteMsg::SetPaused { paused } => exec_set_paused(deps, info, paused),
         ExecuteMsg::SetCommitWindow { start_height, end_height, start_time, end_time } => exec_set_window(deps, info, 0, start_height, end_height, start_time, end_time),
         ExecuteMsg::SetRevealWindow { start_height, end_height, start_time, end_time } => exec_set_window(deps, info, 1, start_height, end_height, start_time, end_time),
         ExecuteMsg::SetClosedWindow { start_height, end_height, start_time, end_time } => exec_set_window(deps, info, 2, start_height, end_height, start_time, end_time),
-        ExecuteMsg::SetNftContract { nft_contract } => exec_set_nft_contract(deps, info, nft_contract),
+        ExecuteMsg::SetSaleWindow { start_height, end_height, start_time, end_time } => exec_set_window(deps, info, 3, start_height, end_height, start_time, end_time),
+        ExecuteMsg::SetNftContract { nft_contract } => exec_set_nft_contract(deps, env, info, nft_contract),
         ExecuteMsg::SetNftCodeId { code_id } => exec_set_nft_code_id(deps, info, code_id),
         ExecuteMsg::InstantiateNftContract { name, symbol, base_uri } => exec_instantiate_nft_contract(deps, env, info, name, symbol, base_uri),
-        ExecuteMsg::Deposit {} => exec_deposit(deps, info),
+        ExecuteMsg::Deposit {} => exec_deposit(deps, env, info),
+        ExecuteMsg::Receive(wrapper) => exec_receive(deps, env, info, wrapper),
+        ExecuteMsg::SetCw20Token { token, base } => exec_set_cw20_token(deps, info, token, base),
         ExecuteMsg::SetVoteState { state } => exec_set_vote_state(deps, info, state),
+        ExecuteMsg::SetTotalSupply { total_supply } => exec_set_total_supply(deps, info, total_supply),
+        ExecuteMsg::CloseSale {} => exec_close_sale(deps, info),
+        ExecuteMsg::SetMintTemplate { template } => exec_set_mint_template(deps, info, template),
+        ExecuteMsg::SetTierPrize { tier, kind, series_id } => exec_set_tier_prize(deps, info, tier, kind, series_id),
+        ExecuteMsg::CommitDistribution { distribution_hash } => exec_commit_distribution(deps, info, distribution_hash),
+        ExecuteMsg::RevealDistribution { items, salt } => exec_reveal_distribution(deps, info, items, salt),
+        ExecuteMsg::PushRevealedMetadata { start_after, limit } => exec_push_revealed_metadata(deps, info, start_after, limit),
         ExecuteMsg::CommitVote { commitment } => exec_commit(deps, env, info, commitment),
         ExecuteMsg::RevealVote { reveal, salt } => exec_reveal(deps, env, info, reveal, salt),
         ExecuteMsg::Finalize {} => exec_finalize(deps, env, info),
@@ -71,7 +130,7 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> R
 
 /// 断言调用者为拥有者，返回最新配置
 fn must_owner(deps: &DepsMut, sender: &cosmwasm_std::Addr) -> Result<Config, ContractError> {
-    let cfg: Config = CONFIG.load(deps.storage)?;
+    let cfg: Config = load_config(deps.storage)?;
     if cfg.owner != *sender {
         return Err(ContractError::Unauthorized);
     }
@@ -81,11 +140,304 @@ fn must_owner(deps: &DepsMut, sender: &cosmwasm_std::Addr) -> Result<Config, Con
 /// 仅拥有者：更新基础币种（用于充值与结算）
 fn exec_set_base(deps: DepsMut, info: MessageInfo, base: Coin) -> Result<Response, ContractError> {
     let mut cfg: Config = must_owner(&deps, &info.sender)?;
+    if cfg.cw20_token.as_ref().is_some_and(|t| base.denom != t.as_str()) {
+        return Err(ContractError::Std(cosmwasm_std::StdError::generic_err("base denom must be the CW20 token address")));
+    }
+    ensure_unlisted_base(&cfg, &base)?;
     cfg.base = base.clone();
     CONFIG.save(deps.storage, &cfg)?;
     Ok(Response::new().add_attributes(vec![attr("action", "set_base"), attr("denom", base.denom), attr("amount", base.amount)]))
 }
 
+/// base 币种不得与附加价格列表中的币种重复
+fn ensure_unlisted_base(cfg: &Config, base: &Coin) -> Result<(), ContractError> {
+    if cfg.pricing.extra_prices.iter().any(|c| c.denom == base.denom) {
+        return Err(ContractError::Std(cosmwasm_std::StdError::generic_err(format!("denom '{}' already in price list", base.denom))));
+    }
+    Ok(())
+}
+
+/// 仅拥有者：发售前将 base 资产设为 CW20 代币（base.denom 改为代币地址，单价保持 base.amount）；
+/// 清除代币时须同时给出新的原生 base，避免 base.denom 仍指向代币地址
+fn exec_set_cw20_token(deps: DepsMut, info: MessageInfo, token: Option<String>, base: Option<Coin>) -> Result<Response, ContractError> {
+    let mut cfg = must_owner(&deps, &info.sender)?;
+    if cfg.next_token_id > 0 {
+        return Err(ContractError::InvalidState);
+    }
+    let token = token.map(|t| validate_address(&deps.as_ref(), &t)).transpose()?;
+    match (&token, base) {
+        (Some(addr), None) => cfg.base.denom = addr.to_string(),
+        (None, Some(base)) => {
+            if cfg.cw20_token.as_ref().is_some_and(|t| base.denom == t.as_str()) {
+                return Err(ContractError::Std(cosmwasm_std::StdError::generic_err("native base denom must not be the CW20 token address")));
+            }
+            ensure_unlisted_base(&cfg, &base)?;
+            cfg.base = base;
+        }
+        (Some(_), Some(_)) => {
+            return Err(ContractError::Std(cosmwasm_std::StdError::generic_err("base is only accepted when clearing the CW20 token")));
+        }
+        (None, None) => {
+            return Err(ContractError::Std(cosmwasm_std::StdError::generic_err("clearing the CW20 token requires a native base")));
+        }
+    }
+    cfg.cw20_token = token;
+    CONFIG.save(deps.storage, &cfg)?;
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_cw20_token"),
+        attr("token", cfg.cw20_token.map(|a| a.to_string()).unwrap_or_default()),
+        attr("denom", cfg.base.denom),
+    ]))
+}
+
+/// 构造向 to 发送资产的消息：base 为 CW20 时使用 `Transfer`，否则使用银行转账
+fn send_asset(cfg: &Config, to: &str, coin: Coin) -> StdResult<cosmwasm_std::CosmosMsg> {
+    match &cfg.cw20_token {
+        Some(token) if coin.denom == token.as_str() => Ok(cosmwasm_std::WasmMsg::Execute {
+            contract_addr: token.to_string(),
+            msg: to_json_binary(&cw20::Cw20ExecuteMsg::Transfer { recipient: to.to_string(), amount: coin.amount })?,
+            funds: vec![],
+        }.into()),
+        _ => Ok(BankMsg::Send { to_address: to.to_string(), amount: vec![coin] }.into()),
+    }
+}
+
+/// 仅拥有者：设置收益分账（基点之和不超过 10000，剩余部分归提取接收者）
+fn exec_set_revenue_split(deps: DepsMut, info: MessageInfo, shares: Vec<RevenueShareMsg>) -> Result<Response, ContractError> {
+    let mut cfg = must_owner(&deps, &info.sender)?;
+    if shares.len() > MAX_REVENUE_SHARES {
+        return Err(ContractError::Std(cosmwasm_std::StdError::generic_err("too many revenue shares")));
+    }
+    let mut split: Vec<RevenueShare> = Vec::with_capacity(shares.len());
+    let mut total_bps: u128 = 0;
+    for share in shares {
+        let address = validate_address(&deps.as_ref(), &share.address)?;
+        if share.share_bps == 0 || split.iter().any(|s| s.address == address) {
+            return Err(ContractError::Std(cosmwasm_std::StdError::generic_err("invalid revenue share")));
+        }
+        total_bps += share.share_bps as u128;
+        split.push(RevenueShare { address, share_bps: share.share_bps });
+    }
+    if total_bps > BPS_DENOMINATOR {
+        return Err(ContractError::Std(cosmwasm_std::StdError::generic_err("revenue shares exceed 100%")));
+    }
+    cfg.revenue_split = split;
+    CONFIG.save(deps.storage, &cfg)?;
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_revenue_split"),
+        attr("shares", cfg.revenue_split.len().to_string()),
+        attr("total_bps", total_bps.to_string()),
+    ]))
+}
+
+/// 仅拥有者：设置协议费（不超过 MAX_PROTOCOL_FEE_BPS，非零时须指定 collector）
+fn exec_set_protocol_fee(deps: DepsMut, info: MessageInfo, fee_bps: u16, collector: Option<String>, on_payouts: bool) -> Result<Response, ContractError> {
+    let mut cfg = must_owner(&deps, &info.sender)?;
+    if fee_bps > MAX_PROTOCOL_FEE_BPS {
+        return Err(ContractError::Std(cosmwasm_std::StdError::generic_err(format!("protocol fee exceeds {} bps", MAX_PROTOCOL_FEE_BPS))));
+    }
+    let collector = collector.map(|c| validate_address(&deps.as_ref(), &c)).transpose()?;
+    if fee_bps > 0 && collector.is_none() {
+        return Err(ContractError::Std(cosmwasm_std::StdError::generic_err("fee collector required")));
+    }
+    cfg.protocol_fee = ProtocolFee { fee_bps, collector, on_payouts };
+    CONFIG.save(deps.storage, &cfg)?;
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_protocol_fee"),
+        attr("fee_bps", fee_bps.to_string()),
+        attr("on_payouts", on_payouts.to_string()),
+    ]))
+}
+
+/// 仅拥有者：切换承诺方案，已有承诺时不可切换（否则无法揭示）
+fn exec_set_commitment_scheme(deps: DepsMut, info: MessageInfo, scheme: CommitmentScheme) -> Result<Response, ContractError> {
+    let mut cfg = must_owner(&deps, &info.sender)?;
+    if COMMITS.prefix(cfg.round).keys(deps.storage, None, None, cosmwasm_std::Order::Ascending).next().is_some() {
+        return Err(ContractError::Std(cosmwasm_std::StdError::generic_err("commitment scheme cannot change after commits exist")));
+    }
+    cfg.commitment_scheme = scheme;
+    CONFIG.save(deps.storage, &cfg)?;
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_commitment_scheme"),
+        attr("scheme", format!("{:?}", cfg.commitment_scheme)),
+    ]))
+}
+
+/// 仅拥有者：设置选票选项（空列表取消选票），本轮已有人揭示时不可修改
+fn exec_set_ballot(deps: DepsMut, info: MessageInfo, options: Vec<String>) -> Result<Response, ContractError> {
+    let cfg = must_owner(&deps, &info.sender)?;
+    if options.len() > MAX_BALLOT_OPTIONS || options.len() == 1 {
+        return Err(ContractError::Std(cosmwasm_std::StdError::generic_err(format!("ballot needs 2-{} options", MAX_BALLOT_OPTIONS))));
+    }
+    if options.iter().any(|o| o.trim().is_empty()) {
+        return Err(ContractError::Std(cosmwasm_std::StdError::generic_err("ballot option cannot be empty")));
+    }
+    if options.iter().enumerate().any(|(i, o)| options[..i].iter().any(|p| p.trim() == o.trim())) {
+        return Err(ContractError::Std(cosmwasm_std::StdError::generic_err("duplicate ballot option")));
+    }
+    // 承诺绑定的是选项序号，本轮已有承诺后选项不可再变
+    if COMMITS.prefix(cfg.round).keys(deps.storage, None, None, cosmwasm_std::Order::Ascending).next().is_some() {
+        return Err(ContractError::Std(cosmwasm_std::StdError::generic_err("ballot cannot change after votes are committed")));
+    }
+    BALLOT.save(deps.storage, &options)?;
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_ballot"),
+        attr("options", options.len().to_string()),
+    ]))
+}
+
+/// 设有选票时将 reveal 解析为选项序号并计票（每个地址每轮一票），未设选票时返回 None
+fn record_ballot(storage: &mut dyn cosmwasm_std::Storage, round: u64, voter: &cosmwasm_std::Addr, reveal: &str) -> Result<Option<u32>, ContractError> {
+    let options = BALLOT.may_load(storage)?.unwrap_or_default();
+    if options.is_empty() {
+        return Ok(None);
+    }
+    let choice = reveal.parse::<u32>().ok().filter(|c| (*c as usize) < options.len())
+        .ok_or_else(|| ContractError::InvalidChoice { choice: reveal.to_string(), options: options.len() })?;
+    if !BALLOT_VOTES.has(storage, (round, voter)) {
+        BALLOT_VOTES.save(storage, (round, voter), &choice)?;
+        VOTE_TALLY.update(storage, (round, choice), |v| -> StdResult<_> { Ok(v.unwrap_or_default() + 1) })?;
+    }
+    Ok(Some(choice))
+}
+
+/// 按协议费率计算 amount 中的费用部分
+fn protocol_fee_of(cfg: &Config, amount: Uint128) -> Uint128 {
+    if cfg.protocol_fee.collector.is_none() {
+        return Uint128::zero();
+    }
+    amount.multiply_ratio(cfg.protocol_fee.fee_bps as u128, BPS_DENOMINATOR)
+}
+
+/// 累计某币种已收取的协议费
+fn record_fee(storage: &mut dyn cosmwasm_std::Storage, denom: &str, fee: Uint128) -> StdResult<()> {
+    FEES_COLLECTED.update(storage, denom, |f| -> StdResult<_> { Ok(f.unwrap_or_default() + fee) })?;
+    Ok(())
+}
+
+/// 合约持有的某币种余额（CW20 base 通过代币合约查询）
+fn balance_of(deps: Deps, env: &Env, cfg: &Config, denom: &str) -> StdResult<Uint128> {
+    match &cfg.cw20_token {
+        Some(token) if denom == token.as_str() => {
+            let res: cw20::BalanceResponse = deps.querier.query_wasm_smart(
+                token.to_string(),
+                &cw20::Cw20QueryMsg::Balance { address: env.contract.address.to_string() },
+            )?;
+            Ok(res.balance)
+        }
+        _ => Ok(deps.querier.query_balance(env.contract.address.to_string(), denom)?.amount),
+    }
+}
+
+/// 某币种仍欠用户的金额：按最高返还倍数预留尚未结清的本金
+fn owed_of(storage: &dyn cosmwasm_std::Storage, denom: &str) -> StdResult<Uint128> {
+    let total = TOTAL_PRINCIPAL.may_load(storage, denom)?.unwrap_or_default();
+    let resolved = RESOLVED_PRINCIPAL.may_load(storage, denom)?.unwrap_or_default();
+    Ok(total.saturating_sub(resolved).checked_mul(Uint128::from(MAX_PAYOUT_MULTIPLIER))?)
+}
+
+/// 某币种的金库情况
+fn treasury_balance(deps: Deps, env: &Env, cfg: &Config, denom: &str) -> StdResult<TreasuryBalance> {
+    let balance = balance_of(deps, env, cfg, denom)?;
+    let owed = owed_of(deps.storage, denom)?;
+    Ok(TreasuryBalance {
+        denom: denom.to_string(),
+        balance,
+        owed,
+        withdrawable: balance.saturating_sub(owed),
+        withdrawn: WITHDRAWN.may_load(deps.storage, denom)?.unwrap_or_default(),
+        fees_collected: FEES_COLLECTED.may_load(deps.storage, denom)?.unwrap_or_default(),
+    })
+}
+
+/// 仅拥有者：提取不欠用户的收益，按分账比例发送，剩余部分发给 recipient（默认拥有者）
+fn exec_withdraw_proceeds(deps: DepsMut, env: Env, info: MessageInfo, amount: Coin, recipient: Option<String>) -> Result<Response, ContractError> {
+    let cfg = must_owner(&deps, &info.sender)?;
+    if amount.amount.is_zero() {
+        return Err(ContractError::Std(cosmwasm_std::StdError::generic_err("zero withdraw amount")));
+    }
+    let recipient = match recipient {
+        Some(r) => validate_address(&deps.as_ref(), &r)?,
+        None => cfg.owner.clone(),
+    };
+
+    let treasury = treasury_balance(deps.as_ref(), &env, &cfg, &amount.denom)?;
+    if amount.amount > treasury.withdrawable {
+        return Err(ContractError::InsufficientProceeds {
+            denom: amount.denom,
+            requested: amount.amount,
+            available: treasury.withdrawable,
+        });
+    }
+    WITHDRAWN.save(deps.storage, &amount.denom, &(treasury.withdrawn + amount.amount))?;
+
+    let mut msgs: Vec<cosmwasm_std::CosmosMsg> = vec![];
+    let mut remaining = amount.amount;
+    for share in &cfg.revenue_split {
+        let part = amount.amount.multiply_ratio(share.share_bps as u128, BPS_DENOMINATOR);
+        if !part.is_zero() {
+            msgs.push(send_asset(&cfg, share.address.as_str(), Coin { denom: amount.denom.clone(), amount: part })?);
+            remaining -= part;
+        }
+    }
+    if !remaining.is_zero() {
+        msgs.push(send_asset(&cfg, recipient.as_str(), Coin { denom: amount.denom.clone(), amount: remaining })?);
+    }
+
+    Ok(Response::new().add_messages(msgs).add_attributes(vec![
+        attr("action", "withdraw_proceeds"),
+        attr("recipient", recipient),
+        attr("amount", amount.amount),
+        attr("denom", amount.denom),
+        attr("to_recipient", remaining),
+    ]))
+}
+
+/// 仅拥有者：设置 base 资产的销售模式
+fn exec_set_sale_mode(deps: DepsMut, info: MessageInfo, mode: SaleMode) -> Result<Response, ContractError> {
+    let mut cfg = must_owner(&deps, &info.sender)?;
+    let label = match &mode {
+        SaleMode::Fixed => "fixed",
+        SaleMode::DutchAuction { start_price, floor_price, start_height, end_height } => {
+            if floor_price.is_zero() || start_price < floor_price || start_height >= end_height {
+                return Err(ContractError::Std(cosmwasm_std::StdError::generic_err("invalid dutch auction parameters")));
+            }
+            "dutch_auction"
+        }
+        SaleMode::BondingCurve { .. } => "bonding_curve",
+    };
+    cfg.sale_mode = mode;
+    CONFIG.save(deps.storage, &cfg)?;
+    Ok(Response::new().add_attributes(vec![attr("action", "set_sale_mode"), attr("mode", label)]))
+}
+
+/// 仅拥有者：设置 base 之外的币种价格、批量折扣与价格曲线
+fn exec_set_pricing(deps: DepsMut, info: MessageInfo, extra_prices: Vec<Coin>, bulk_discounts: Vec<BulkDiscount>, curve: PriceCurve) -> Result<Response, ContractError> {
+    let mut cfg = must_owner(&deps, &info.sender)?;
+
+    let mut denoms: Vec<String> = vec![cfg.base.denom.clone()];
+    for price in &extra_prices {
+        if price.denom.is_empty() || price.amount.is_zero() || denoms.contains(&price.denom) {
+            return Err(ContractError::Std(cosmwasm_std::StdError::generic_err(format!("invalid price for denom '{}'", price.denom))));
+        }
+        denoms.push(price.denom.clone());
+    }
+    if bulk_discounts.iter().any(|d| d.min_units == 0 || d.discount_bps as u128 >= BPS_DENOMINATOR) {
+        return Err(ContractError::Std(cosmwasm_std::StdError::generic_err("invalid bulk discount")));
+    }
+    if let PriceCurve::StepUp { step_units: 0, .. } = curve {
+        return Err(ContractError::Std(cosmwasm_std::StdError::generic_err("step_units must be positive")));
+    }
+
+    cfg.pricing = Pricing { extra_prices, bulk_discounts, curve };
+    CONFIG.save(deps.storage, &cfg)?;
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_pricing"),
+        attr("denoms", denoms.join(",")),
+        attr("bulk_discounts", cfg.pricing.bulk_discounts.len().to_string()),
+    ]))
+}
+
 /// 仅拥有者：更新投票阶段
 fn exec_set_vote_state(deps: DepsMut, info: MessageInfo, state: VoteState) -> Result<Response, ContractError> {
     let mut cfg = must_owner(&deps, &info.sender)?;
@@ -93,9 +445,43 @@ fn exec_set_vote_state(deps: DepsMut, info: MessageInfo, state: VoteState) -> Re
     // 验证状态转换是否合法
     validate_state_transition(&cfg.vote_state, &state)?;
     
+    let event = events::phase_change_event(&cfg.vote_state, &state);
+    // 从 Closed 重新开始即进入新一轮；承诺、揭示与分层均按轮次存储，上一轮的记录不再参与结算
+    if cfg.vote_state == VoteState::Closed && state == VoteState::Commit {
+        cfg.round += 1;
+        SETTLED.remove(deps.storage);
+    }
     cfg.vote_state = state.clone();
     CONFIG.save(deps.storage, &cfg)?;
-    Ok(Response::new().add_attributes(vec![attr("action", "set_vote_state"), attr("state", format_state(&state))]))
+    Ok(Response::new()
+        .add_event(event)
+        .add_attributes(vec![attr("action", "set_vote_state"), attr("state", format_state(&state))]))
+}
+
+/// 仅拥有者：售罄前下调总供应量（不得低于已铸造数量）
+fn exec_set_total_supply(deps: DepsMut, info: MessageInfo, total_supply: u64) -> Result<Response, ContractError> {
+    let mut cfg = must_owner(&deps, &info.sender)?;
+    if cfg.next_token_id >= cfg.total_supply {
+        return Err(ContractError::NoNftsAvailable);
+    }
+    let min = core::cmp::max(1, cfg.next_token_id);
+    if total_supply < min || total_supply > cfg.total_supply {
+        return Err(ContractError::InvalidTotalSupply { total_supply, min, max: cfg.total_supply });
+    }
+    cfg.total_supply = total_supply;
+    CONFIG.save(deps.storage, &cfg)?;
+    Ok(Response::new().add_attributes(vec![attr("action", "set_total_supply"), attr("total_supply", total_supply.to_string())]))
+}
+
+/// 仅拥有者：提前结束发售，总供应量收缩为已铸造数量
+fn exec_close_sale(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut cfg = must_owner(&deps, &info.sender)?;
+    if cfg.next_token_id >= cfg.total_supply {
+        return Err(ContractError::NoNftsAvailable);
+    }
+    cfg.total_supply = cfg.next_token_id;
+    CONFIG.save(deps.storage, &cfg)?;
+    Ok(Response::new().add_attributes(vec![attr("action", "close_sale"), attr("total_supply", cfg.total_supply.to_string())]))
 }
 
 /// 仅拥有者：设置暂停标记
@@ -106,24 +492,46 @@ fn exec_set_paused(deps: DepsMut, info: MessageInfo, paused: bool) -> Result<Res
     Ok(Response::new().add_attributes(vec![attr("action", "set_paused"), attr("paused", paused.to_string())]))
 }
 
-/// 仅拥有者：设置阶段窗口（0=commit,1=reveal,2=closed）
+/// 仅拥有者：设置阶段窗口（0=commit,1=reveal,2=closed,3=sale）
 fn exec_set_window(deps: DepsMut, info: MessageInfo, which: u8, start_height: Option<u64>, end_height: Option<u64>, start_time: Option<u64>, end_time: Option<u64>) -> Result<Response, ContractError> {
     let mut cfg = must_owner(&deps, &info.sender)?;
     let w = PhaseWindow { start_height, end_height, start_time, end_time };
-    match which { 0 => cfg.commit_window = w, 1 => cfg.reveal_window = w, _ => cfg.closed_window = w };
+    match which { 0 => cfg.commit_window = w, 1 => cfg.reveal_window = w, 2 => cfg.closed_window = w, _ => cfg.sale_window = w };
     CONFIG.save(deps.storage, &cfg)?;
     Ok(Response::new().add_attributes(vec![attr("action", "set_window"), attr("which", which.to_string())]))
 }
 
-/// 仅拥有者：设置NFT合约地址
-fn exec_set_nft_contract(deps: DepsMut, info: MessageInfo, nft_contract: String) -> Result<Response, ContractError> {
+/// 仅拥有者：设置NFT合约地址（目标须为以本合约为铸造者的 luckee_nft，开始铸造后不可更换）
+fn exec_set_nft_contract(deps: DepsMut, env: Env, info: MessageInfo, nft_contract: String) -> Result<Response, ContractError> {
     let mut cfg = must_owner(&deps, &info.sender)?;
+    if cfg.next_token_id > 0 {
+        return Err(ContractError::NftContractLocked);
+    }
     let validated_addr = validate_address(&deps.as_ref(), &nft_contract)?;
+    verify_nft_contract(deps.as_ref(), &env, &cfg, &validated_addr)?;
     cfg.nft_contract = Some(validated_addr.clone());
     CONFIG.save(deps.storage, &cfg)?;
     Ok(Response::new().add_attributes(vec![attr("action", "set_nft_contract"), attr("nft_contract", validated_addr)]))
 }
 
+/// 校验 NFT 合约：代码 ID 须与 nft_code_id 一致（已设置时），铸造者须为本合约
+fn verify_nft_contract(deps: Deps, env: &Env, cfg: &Config, addr: &cosmwasm_std::Addr) -> Result<(), ContractError> {
+    let invalid = |reason: String| ContractError::InvalidNftContract { address: addr.to_string(), reason };
+    let contract_info = deps.querier.query_wasm_contract_info(addr)
+        .map_err(|e| invalid(format!("not a contract ({})", e)))?;
+    if let Some(code_id) = cfg.nft_code_id {
+        if contract_info.code_id != code_id {
+            return Err(invalid(format!("code id {} does not match {}", contract_info.code_id, code_id)));
+        }
+    }
+    let minter: NftMinterResponse = deps.querier.query_wasm_smart(addr, &luckee_nft::msg::QueryMsg::Minter {})
+        .map_err(|e| invalid(format!("minter query failed ({})", e)))?;
+    if minter.minter.as_deref() != Some(env.contract.address.as_str()) {
+        return Err(invalid(format!("minter is {}", minter.minter.as_deref().unwrap_or("unset"))));
+    }
+    Ok(())
+}
+
 /// 仅拥有者：设置NFT合约代码ID
 fn exec_set_nft_code_id(deps: DepsMut, info: MessageInfo, code_id: u64) -> Result<Response, ContractError> {
     let mut cfg = must_owner(&deps, &info.sender)?;
@@ -164,14 +572,14 @@ fn exec_instantiate_nft_contract(
     
     let instantiate_msg_binary = cosmwasm_std::to_json_binary(&instantiate_msg)?;
     
-    // 创建实例化子消息
-    let submsg = cosmwasm_std::SubMsg::new(cosmwasm_std::WasmMsg::Instantiate {
+    // 创建实例化子消息，成功后在 reply 中记录新合约地址
+    let submsg = cosmwasm_std::SubMsg::reply_on_success(cosmwasm_std::WasmMsg::Instantiate {
         admin: Some(env.contract.address.to_string()), // 盲盒合约作为管理员
         code_id,
         msg: instantiate_msg_binary,
         funds: vec![],
         label: format!("luckee_nft_{}", name),
-    });
+    }, INSTANTIATE_NFT_REPLY_ID);
     
     Ok(Response::new()
         .add_submessage(submsg)
@@ -183,6 +591,163 @@ fn exec_instantiate_nft_contract(
         ]))
 }
 
+/// 仅拥有者：设置铸造元数据模板
+fn exec_set_mint_template(deps: DepsMut, info: MessageInfo, template: MintTemplate) -> Result<Response, ContractError> {
+    let mut cfg = must_owner(&deps, &info.sender)?;
+    if template.series_pattern.is_empty() {
+        return Err(ContractError::Std(cosmwasm_std::StdError::generic_err("series_pattern cannot be empty")));
+    }
+    if template.skus.iter().any(|r| r.start_id > r.end_id || r.sku.is_empty()) {
+        return Err(ContractError::Std(cosmwasm_std::StdError::generic_err("invalid sku rule")));
+    }
+    cfg.mint_template = template;
+    CONFIG.save(deps.storage, &cfg)?;
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_mint_template"),
+        attr("series_pattern", cfg.mint_template.series_pattern),
+        attr("group_size", cfg.mint_template.group_size.to_string()),
+    ]))
+}
+
+/// 仅拥有者：设置某一档的奖品 NFT（kind 为 None 时取消该档奖品）
+fn exec_set_tier_prize(deps: DepsMut, info: MessageInfo, tier: u8, kind: Option<luckee_nft::types::NftKind>, series_id: Option<String>) -> Result<Response, ContractError> {
+    must_owner(&deps, &info.sender)?;
+    if !(1..=3).contains(&tier) {
+        return Err(ContractError::InvalidTier { tier });
+    }
+
+    let Some(kind) = kind else {
+        TIER_PRIZES.remove(deps.storage, tier);
+        return Ok(Response::new().add_attributes(vec![attr("action", "set_tier_prize"), attr("tier", tier.to_string()), attr("cleared", "true")]));
+    };
+
+    let series_id = series_id.unwrap_or_else(|| format!("blind_box_prize_tier_{}", tier));
+    if series_id.is_empty() {
+        return Err(ContractError::Std(cosmwasm_std::StdError::generic_err("series_id cannot be empty")));
+    }
+    // 保留已铸造数量，保证系列内序号连续
+    let minted = TIER_PRIZES.may_load(deps.storage, tier)?.map(|p| p.minted).unwrap_or(0);
+    TIER_PRIZES.save(deps.storage, tier, &TierPrize { kind, series_id: series_id.clone(), minted })?;
+    Ok(Response::new().add_attributes(vec![attr("action", "set_tier_prize"), attr("tier", tier.to_string()), attr("series_id", series_id)]))
+}
+
+/// 仅拥有者：发售前承诺隐藏分布的哈希（见 `distribution_hash`）
+fn exec_commit_distribution(deps: DepsMut, info: MessageInfo, distribution_hash: String) -> Result<Response, ContractError> {
+    let cfg = must_owner(&deps, &info.sender)?;
+    // 一旦开始发售即不可再更改承诺
+    if cfg.next_token_id > 0 {
+        return Err(ContractError::InvalidState);
+    }
+    if distribution_hash.len() != 64 || hex::decode(&distribution_hash).is_err() {
+        return Err(ContractError::Std(cosmwasm_std::StdError::generic_err("distribution_hash must be a hex encoded sha256 digest")));
+    }
+    REVEAL_PLAN.save(deps.storage, &RevealPlan { distribution_hash: distribution_hash.clone(), seed: None, verified: false, revealed: false, revealed_count: 0 })?;
+    Ok(Response::new().add_attributes(vec![attr("action", "commit_distribution"), attr("distribution_hash", distribution_hash)]))
+}
+
+/// 仅拥有者：公布隐藏分布，校验承诺哈希后按结算种子洗牌，一次性保存每个已售 token 的隐藏内容
+fn exec_reveal_distribution(deps: DepsMut, info: MessageInfo, items: Vec<HiddenItem>, salt: String) -> Result<Response, ContractError> {
+    let cfg = must_owner(&deps, &info.sender)?;
+    let mut plan = REVEAL_PLAN.may_load(deps.storage)?
+        .ok_or_else(|| ContractError::Std(cosmwasm_std::StdError::generic_err("distribution not committed")))?;
+    if plan.verified {
+        return Err(ContractError::InvalidState);
+    }
+    // 种子在结算时固定，之前不可揭晓
+    let seed = plan.seed.clone().ok_or(ContractError::InvalidState)?;
+
+    // 总供应量可能在承诺后被下调，只要求分布覆盖所有已铸造 token
+    if (items.len() as u64) < cfg.next_token_id {
+        return Err(ContractError::Std(cosmwasm_std::StdError::generic_err(format!(
+            "distribution must contain at least {} items", cfg.next_token_id
+        ))));
+    }
+    if distribution_hash(&items, &salt)? != plan.distribution_hash {
+        return Err(ContractError::Std(cosmwasm_std::StdError::generic_err("distribution hash mismatch")));
+    }
+
+    // 种子固定后不再铸造，已售 token 的分配结果在此一次写入，后续分页只读存储
+    let order = shuffle_indices(&seed, items.len());
+    for token_id in 0..cfg.next_token_id {
+        REVEALED_ITEMS.save(deps.storage, token_id, &items[order[token_id as usize]])?;
+    }
+    plan.verified = true;
+    REVEAL_PLAN.save(deps.storage, &plan)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "reveal_distribution"),
+        attr("seed", seed),
+        attr("assigned", cfg.next_token_id.to_string()),
+    ]))
+}
+
+/// 仅拥有者：按 token_id 分页将已保存的隐藏内容推送到 luckee_nft 元数据
+fn exec_push_revealed_metadata(deps: DepsMut, info: MessageInfo, start_after: Option<u64>, limit: Option<u32>) -> Result<Response, ContractError> {
+    let cfg = must_owner(&deps, &info.sender)?;
+    let mut plan = REVEAL_PLAN.may_load(deps.storage)?
+        .ok_or_else(|| ContractError::Std(cosmwasm_std::StdError::generic_err("distribution not committed")))?;
+    if !plan.verified || plan.revealed {
+        return Err(ContractError::InvalidState);
+    }
+    let nft_contract = cfg.nft_contract.as_ref()
+        .ok_or_else(|| ContractError::Std(cosmwasm_std::StdError::generic_err("NFT contract not set")))?;
+
+    // 每次最多推送 limit 个 token，起点不得越过已推送的进度（重复推送结果相同）
+    let start = start_after.map(|t| t.saturating_add(1)).unwrap_or(0);
+    if start > plan.revealed_count {
+        return Err(ContractError::Std(cosmwasm_std::StdError::generic_err(format!(
+            "reveal must continue from token {}", plan.revealed_count
+        ))));
+    }
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as u64;
+    let end = start.saturating_add(limit).min(cfg.next_token_id);
+
+    let mut msgs: Vec<cosmwasm_std::CosmosMsg> = vec![];
+    for token_id in start..end {
+        let item = REVEALED_ITEMS.load(deps.storage, token_id)?;
+        let mut extension = build_nft_meta(&cfg, token_id);
+        extension.kind = item.kind;
+        let update_msg = luckee_nft::msg::ExecuteMsg::UpdateMetadata { token_id, extension };
+        msgs.push(cosmwasm_std::WasmMsg::Execute {
+            contract_addr: nft_contract.to_string(),
+            msg: to_json_binary(&update_msg)?,
+            funds: vec![],
+        }.into());
+    }
+
+    plan.revealed_count = plan.revealed_count.max(end);
+    plan.revealed = plan.revealed_count >= cfg.next_token_id;
+    REVEAL_PLAN.save(deps.storage, &plan)?;
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attributes(vec![
+            attr("action", "push_revealed_metadata"),
+            attr("revealed", (end.saturating_sub(start)).to_string()),
+            attr("revealed_count", plan.revealed_count.to_string()),
+            attr("complete", plan.revealed.to_string()),
+        ]))
+}
+
+/// 计算隐藏分布的承诺哈希：hex(sha256(json(items) || salt))
+pub fn distribution_hash(items: &[HiddenItem], salt: &str) -> StdResult<String> {
+    let mut hasher = sha2::Sha256::new();
+    hasher.update(cosmwasm_std::to_json_vec(items)?);
+    hasher.update(salt.as_bytes());
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// 基于种子的确定性洗牌（Fisher–Yates，随机源为 sha256(seed|i)）：返回值第 t 位为 token t 对应的分布下标
+pub fn shuffle_indices(seed: &str, n: usize) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..n).collect();
+    for i in (1..n).rev() {
+        let hash = sha2::Sha256::digest(format!("{}|{}", seed, i).as_bytes());
+        let r = u64::from_be_bytes([hash[0], hash[1], hash[2], hash[3], hash[4], hash[5], hash[6], hash[7]]);
+        order.swap(i, (r % (i as u64 + 1)) as usize);
+    }
+    order
+}
+
 /// 判断当前区块是否命中窗口设置（高度/时间均为可选闭区间）
 fn in_window(env: &Env, w: &PhaseWindow) -> bool {
     if let Some(s) = w.start_height { if env.block.height < s { return false; } }
@@ -206,6 +771,33 @@ fn format_state_scale(scale: &Scale) -> String {
     } 
 }
 
+/// 将规模枚举转为 luckee_nft 的规模类型
+fn to_nft_scale(scale: &Scale) -> luckee_nft::types::Scale {
+    match scale {
+        Scale::Tiny => luckee_nft::types::Scale::Tiny,
+        Scale::Small => luckee_nft::types::Scale::Small,
+        Scale::Medium => luckee_nft::types::Scale::Medium,
+        Scale::Large => luckee_nft::types::Scale::Large,
+        Scale::Huge => luckee_nft::types::Scale::Huge,
+    }
+}
+
+/// 按铸造模板生成指定 token 的 NFT 元数据
+fn build_nft_meta(cfg: &Config, token_id: u64) -> luckee_nft::types::NftMeta {
+    let template = &cfg.mint_template;
+    luckee_nft::types::NftMeta {
+        kind: template.kind.clone(),
+        scale_origin: to_nft_scale(&cfg.scale),
+        physical_sku: template.skus.iter()
+            .find(|r| r.start_id <= token_id && token_id <= r.end_id)
+            .map(|r| r.sku.clone()),
+        crafted_from: None,
+        series_id: template.series_pattern.replace("{scale}", &format_state_scale(&cfg.scale)),
+        collection_group_id: if template.group_size > 0 { Some(format!("group_{}", token_id / template.group_size)) } else { None },
+        serial_in_series: token_id,
+    }
+}
+
 /// 验证状态转换是否合法
 fn validate_state_transition(current: &VoteState, new: &VoteState) -> Result<(), ContractError> {
     match (current, new) {
@@ -218,84 +810,230 @@ fn validate_state_transition(current: &VoteState, new: &VoteState) -> Result<(),
     }
 }
 
-/// 验证地址，对明显无效的地址返回错误
+/// 验证地址：由 `deps.api` 按链上 bech32 前缀严格校验，拒绝非规范格式（如大写）
 fn validate_address(deps: &Deps, address: &str) -> Result<cosmwasm_std::Addr, ContractError> {
-    if address.is_empty() || address == "invalid_address" {
-        return Err(ContractError::Std(cosmwasm_std::StdError::generic_err("Invalid address")));
+    deps.api.addr_validate(address)
+        .map_err(|_| ContractError::Std(cosmwasm_std::StdError::generic_err(format!("Invalid address: {}", address))))
+}
+
+/// 指定币种在当前区块的初始单价（base 按销售模式计算，其他币种取价格表）
+fn unit_price(cfg: &Config, env: &Env, denom: &str) -> Option<Uint128> {
+    if denom != cfg.base.denom {
+        return cfg.pricing.extra_prices.iter().find(|c| c.denom == denom).map(|c| c.amount);
+    }
+    match &cfg.sale_mode {
+        SaleMode::Fixed | SaleMode::BondingCurve { .. } => Some(cfg.base.amount),
+        SaleMode::DutchAuction { start_price, floor_price, start_height, end_height } => {
+            let height = env.block.height;
+            if height <= *start_height {
+                Some(*start_price)
+            } else if height >= *end_height {
+                Some(*floor_price)
+            } else {
+                // 线性衰减：start - (start - floor) * elapsed / duration
+                let decay = (*start_price - *floor_price).multiply_ratio(height - start_height, end_height - start_height);
+                Some(*start_price - decay)
+            }
+        }
+    }
+}
+
+/// 按销售模式与价格曲线计算某币种在某个 token_id 的单价
+fn token_price(cfg: &Config, denom: &str, price: Uint128, token_id: u64) -> StdResult<Uint128> {
+    let mut price = price;
+    if let SaleMode::BondingCurve { slope } = &cfg.sale_mode {
+        if denom == cfg.base.denom {
+            price = price.checked_add(slope.checked_mul(Uint128::from(token_id))?)?;
+        }
+    }
+    match &cfg.pricing.curve {
+        PriceCurve::Flat => Ok(price),
+        PriceCurve::StepUp { step_units, increase_bps } => {
+            let steps = (token_id / step_units) as u128;
+            price
+                .checked_multiply_ratio(BPS_DENOMINATOR + steps * (*increase_bps as u128), BPS_DENOMINATOR)
+                .map_err(|e| cosmwasm_std::StdError::generic_err(e.to_string()))
+        }
+    }
+}
+
+/// 单笔购买 units 个时适用的批量折扣（基点）
+fn bulk_discount_bps(pricing: &Pricing, units: u64) -> u16 {
+    pricing.bulk_discounts.iter()
+        .filter(|d| units >= d.min_units)
+        .map(|d| d.discount_bps)
+        .max()
+        .unwrap_or(0)
+}
+
+/// 从 start_id 起购买 units 个的总价（逐个累加单价后应用批量折扣）
+fn quote_cost(cfg: &Config, denom: &str, price: Uint128, start_id: u64, units: u64) -> StdResult<Uint128> {
+    let pricing = &cfg.pricing;
+    let mut gross = Uint128::zero();
+    for token_id in start_id..start_id + units {
+        gross = gross.checked_add(token_price(cfg, denom, price, token_id)?)?;
+    }
+    Ok(gross.multiply_ratio(BPS_DENOMINATOR - bulk_discount_bps(pricing, units) as u128, BPS_DENOMINATOR))
+}
+
+/// 在 funds 与剩余供应量内可购买的最大数量及其总价
+fn quote_max_units(cfg: &Config, denom: &str, price: Uint128, start_id: u64, max_units: u64, funds: Uint128) -> StdResult<(u64, Uint128)> {
+    let pricing = &cfg.pricing;
+    let max_bps = pricing.bulk_discounts.iter().map(|d| d.discount_bps).max().unwrap_or(0);
+    let mut gross = Uint128::zero();
+    let mut best = (0u64, Uint128::zero());
+    for units in 1..=max_units {
+        gross = gross.checked_add(token_price(cfg, denom, price, start_id + units - 1)?)?;
+        // 即便按最大折扣也超出预算，继续增加数量只会更贵
+        if gross.multiply_ratio(BPS_DENOMINATOR - max_bps as u128, BPS_DENOMINATOR) > funds {
+            break;
+        }
+        let cost = gross.multiply_ratio(BPS_DENOMINATOR - bulk_discount_bps(pricing, units) as u128, BPS_DENOMINATOR);
+        if cost <= funds {
+            best = (units, cost);
+        }
+    }
+    Ok(best)
+}
+
+/// 地址在各币种下尚未结清的本金（base 在前，其余按币种排序，均非零）
+fn principals_of(storage: &dyn cosmwasm_std::Storage, cfg: &Config, addr: &cosmwasm_std::Addr) -> StdResult<Vec<Coin>> {
+    let mut coins = vec![];
+    let base = DEPOSITS.may_load(storage, addr.clone())?.map(|p| p.principal).unwrap_or_default();
+    let base = open_principal(storage, addr, &cfg.base.denom, base)?;
+    if !base.is_zero() {
+        coins.push(Coin { denom: cfg.base.denom.clone(), amount: base });
+    }
+    for item in EXTRA_DEPOSITS.prefix(addr).range(storage, None, None, cosmwasm_std::Order::Ascending) {
+        let (denom, amount) = item?;
+        let amount = open_principal(storage, addr, &denom, amount)?;
+        if !amount.is_zero() {
+            coins.push(Coin { denom, amount });
+        }
     }
-    deps.api.addr_validate(address).or_else(|_| {
-        // 在测试环境中，如果地址验证失败，尝试使用unchecked
-        Ok::<cosmwasm_std::Addr, cosmwasm_std::StdError>(cosmwasm_std::Addr::unchecked(address))
-    }).map_err(|_| ContractError::Std(cosmwasm_std::StdError::generic_err("Invalid address")))
+    Ok(coins)
 }
 
 /// 充值：按基础币倍数计算铸造数量，通过外部NFT合约铸造 NFT
-fn exec_deposit(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
-    let cfg = CONFIG.load(deps.storage)?;
+fn exec_deposit(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let cfg = load_config(deps.storage)?;
+
+    // 只接受单一币种，避免多带的币既不计本金也不退还；币种须在价格表中
+    if info.funds.len() > 1 {
+        return Err(ContractError::MultipleCoins { count: info.funds.len() });
+    }
+    let sent = info
+        .funds
+        .first()
+        .filter(|c| unit_price(&cfg, &env, &c.denom).is_some())
+        .cloned()
+        .unwrap_or(Coin { denom: cfg.base.denom.clone(), amount: Uint128::zero() });
+    deposit_funds(deps, env, cfg, info.sender, sent)
+}
+
+/// CW20 入口：仅接受来自 base CW20 合约的 `Send`，钩子消息为 `Deposit {}`
+fn exec_receive(deps: DepsMut, env: Env, info: MessageInfo, wrapper: Cw20ReceiveMsg) -> Result<Response, ContractError> {
+    let cfg = load_config(deps.storage)?;
+    let token = cfg.cw20_token.clone().ok_or(ContractError::Unauthorized)?;
+    if info.sender != token {
+        return Err(ContractError::Unauthorized);
+    }
+    let hook: Cw20HookMsg = cosmwasm_std::from_json(&wrapper.msg)?;
+    match hook {
+        Cw20HookMsg::Deposit {} => {
+            let sender = validate_address(&deps.as_ref(), &wrapper.sender)?;
+            let sent = Coin { denom: token.to_string(), amount: wrapper.amount };
+            deposit_funds(deps, env, cfg, sender, sent)
+        }
+    }
+}
+
+/// 充值公共逻辑：原生币与 CW20 共用的计价、记账与批量铸造
+fn deposit_funds(deps: DepsMut, env: Env, cfg: Config, sender: cosmwasm_std::Addr, sent: Coin) -> Result<Response, ContractError> {
     if cfg.paused { return Err(ContractError::InvalidState); }
 
+    // 揭晓种子固定后，分布与种子公开即可推算后续 token 的内容，停止发售
+    if REVEAL_PLAN.may_load(deps.storage)?.is_some_and(|plan| plan.seed.is_some()) {
+        return Err(ContractError::RevealSeedFixed);
+    }
+
+    // 验证是否在发售窗口内
+    if !in_window(&env, &cfg.sale_window) {
+        return Err(ContractError::OutsideWindow {
+            current: env.block.time.seconds(),
+            start: cfg.sale_window.start_time.unwrap_or(0),
+            end: cfg.sale_window.end_time.unwrap_or(u64::MAX),
+        });
+    }
+
     // 检查是否设置了NFT合约
     let nft_contract = cfg.nft_contract.as_ref()
         .ok_or_else(|| ContractError::Std(cosmwasm_std::StdError::generic_err("NFT contract not set")))?;
 
-    let base_denom = cfg.base.denom.clone();
-    let base_amount = cfg.base.amount;
+    let price = unit_price(&cfg, &env, &sent.denom).unwrap_or_default();
 
-    let sent = info
-        .funds
-        .iter()
-        .find(|c| c.denom == base_denom)
-        .cloned()
-        .unwrap_or(Coin { denom: base_denom.clone(), amount: Uint128::zero() });
+    if sent.amount.is_zero() || price.is_zero() || sent.amount < price {
+        return Err(ContractError::Std(cosmwasm_std::StdError::generic_err("insufficient base sent")));
+    }
+
+    // 检查是否还有NFT可供铸造
+    if cfg.next_token_id >= cfg.total_supply {
+        return Err(ContractError::NoNftsAvailable);
+    }
 
-    if sent.amount.is_zero() || sent.amount < base_amount {
+    // 计算要铸造的NFT数量（考虑价格曲线与批量折扣）
+    let (units, cost) = quote_max_units(&cfg, &sent.denom, price, cfg.next_token_id, cfg.total_supply - cfg.next_token_id, sent.amount)?;
+    if units == 0 {
         return Err(ContractError::Std(cosmwasm_std::StdError::generic_err("insufficient base sent")));
     }
 
+    // 只收取实际成交金额，多付部分退还
+    let (charged, refund) = (cost, sent.amount - cost);
+
+    // 协议费从实收金额中扣除，本金按扣费后的净额记录
+    let fee = protocol_fee_of(&cfg, charged);
+    let charged = charged - fee;
+    if !fee.is_zero() {
+        record_fee(deps.storage, &sent.denom, fee)?;
+    }
+
+    // 上次结算后的新充值重新计入应付本金，当前轮次需重新结算
+    let total_before = if sent.denom == cfg.base.denom {
+        DEPOSITS.may_load(deps.storage, sender.clone())?.map(|p| p.principal).unwrap_or_default()
+    } else {
+        EXTRA_DEPOSITS.may_load(deps.storage, (&sender, sent.denom.as_str()))?.unwrap_or_default()
+    };
+    rebase_principal(deps.storage, &sender, &sent.denom, total_before)?;
+    SETTLED.remove(deps.storage);
+
     // Record deposit
-    let existing = DEPOSITS.may_load(deps.storage, info.sender.clone())?.unwrap_or(Payout { principal: Uint128::zero() });
-    let updated = Payout { principal: existing.principal + sent.amount };
-    DEPOSITS.save(deps.storage, info.sender.clone(), &updated)?;
+    if sent.denom == cfg.base.denom {
+        let existing = DEPOSITS.may_load(deps.storage, sender.clone())?.unwrap_or(Payout { principal: Uint128::zero() });
+        let updated = Payout { principal: existing.principal + charged };
+        save_deposit(deps.storage, &sender, &updated)?;
+        let clearing = token_price(&cfg, &sent.denom, price, cfg.next_token_id + units - 1)?;
+        LAST_CLEARING_PRICE.save(deps.storage, &Coin { denom: sent.denom.clone(), amount: clearing })?;
+    } else {
+        EXTRA_DEPOSITS.update(deps.storage, (&sender, sent.denom.as_str()), |p| -> StdResult<_> {
+            Ok(p.unwrap_or_default() + charged)
+        })?;
+    }
+    TOTAL_PRINCIPAL.update(deps.storage, sent.denom.as_str(), |p| -> StdResult<_> {
+        Ok(p.unwrap_or_default() + charged)
+    })?;
 
-    // 计算要铸造的NFT数量
-    let multiples = sent.amount / base_amount;
     let mut minted: u64 = 0;
     let mut next_id = cfg.next_token_id;
     let mut cfg_mut = cfg.clone();
     
-    // 检查是否还有NFT可供铸造
-    if next_id >= cfg_mut.total_supply {
-        return Err(ContractError::NoNftsAvailable);
-    }
-    
     // 准备批量铸造消息
     let mut batch_mints = Vec::new();
-    for _ in 0..multiples.u128() {
-        if next_id >= cfg_mut.total_supply {
-            break;
-        }
-        
-        // 创建NFT元数据
-        let nft_meta = luckee_nft::types::NftMeta {
-            kind: luckee_nft::types::NftKind::Clover, // 盲盒NFT默认为四叶草
-            scale_origin: match cfg_mut.scale {
-                crate::state::Scale::Tiny => luckee_nft::types::Scale::Tiny,
-                crate::state::Scale::Small => luckee_nft::types::Scale::Small,
-                crate::state::Scale::Medium => luckee_nft::types::Scale::Medium,
-                crate::state::Scale::Large => luckee_nft::types::Scale::Large,
-                crate::state::Scale::Huge => luckee_nft::types::Scale::Huge,
-            },
-            physical_sku: None,
-            crafted_from: None,
-            series_id: format!("blind_box_{}", format_state_scale(&cfg_mut.scale)),
-            collection_group_id: Some(format!("group_{}", next_id / 1000)), // 每1000个NFT一组
-            serial_in_series: next_id,
-        };
-        
+    for _ in 0..units {
+        // 按铸造模板创建NFT元数据
         batch_mints.push(luckee_nft::msg::BatchMintItem {
             token_id: next_id,
-            owner: info.sender.to_string(),
-            extension: nft_meta,
+            owner: sender.to_string(),
+            extension: build_nft_meta(&cfg_mut, next_id),
         });
         
         next_id += 1;
@@ -306,6 +1044,9 @@ fn exec_deposit(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractEr
         return Err(ContractError::NoNftsAvailable);
     }
     
+    // 记录购买的 token_id 区间
+    PURCHASES.save(deps.storage, (&sender, cfg.next_token_id), &minted)?;
+
     // 更新配置
     cfg_mut.next_token_id = next_id;
     CONFIG.save(deps.storage, &cfg_mut)?;
@@ -320,60 +1061,264 @@ fn exec_deposit(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractEr
         funds: vec![],
     });
 
-    Ok(Response::new()
+    let paid = Coin { denom: sent.denom.clone(), amount: sent.amount - refund };
+    let mut res = Response::new()
         .add_submessage(submsg)
-        .add_attributes(vec![
-            attr("action", "deposit"),
-            attr("from", info.sender),
-            attr("amount", sent.amount),
-            attr("minted", minted.to_string()),
-        ]))
+        .add_event(events::deposit_event(&sender, &paid, cost, cfg.next_token_id, minted));
+    if !refund.is_zero() {
+        res = res.add_message(send_asset(&cfg, sender.as_str(), Coin { denom: sent.denom.clone(), amount: refund })?);
+    }
+    if let Some(collector) = cfg.protocol_fee.collector.as_ref().filter(|_| !fee.is_zero()) {
+        res = res.add_message(send_asset(&cfg, collector.as_str(), Coin { denom: sent.denom.clone(), amount: fee })?);
+    }
+
+    Ok(res.add_attributes(vec![
+        attr("action", "deposit"),
+        attr("from", sender),
+        attr("amount", sent.amount),
+        attr("denom", sent.denom),
+        attr("cost", cost),
+        attr("refund", refund),
+        attr("fee", fee),
+        attr("minted", minted.to_string()),
+    ]))
 }
 
-/// 存储投票承诺字符串（后续将用 sha256(addr|reveal|salt) 进行验证）
+/// 存储投票承诺字符串（揭示时按 `commitment::compute_commitment` 验证）
 fn exec_commit(deps: DepsMut, env: Env, info: MessageInfo, commitment: String) -> Result<Response, ContractError> {
-    let cfg = CONFIG.load(deps.storage)?;
+    let cfg = load_config(deps.storage)?;
+    check_commit_phase(&env, &cfg)?;
+    save_commit(deps.storage, cfg.round, &info.sender, &CommitInfo { commitment: commitment.clone() })?;
+    Ok(Response::new()
+        .add_event(events::commit_event(&info.sender, &commitment))
+        .add_attributes(vec![attr("action", "commit"), attr("voter", info.sender), attr("commitment", commitment)]))
+}
+
+/// 承诺阶段与提交窗口校验
+fn check_commit_phase(env: &Env, cfg: &Config) -> Result<(), ContractError> {
     if !matches!(cfg.vote_state, VoteState::Commit) { return Err(ContractError::CommitNotActive); }
     
     // 验证是否在提交窗口内
-    if !in_window(&env, &cfg.commit_window) {
+    if !in_window(env, &cfg.commit_window) {
         return Err(ContractError::OutsideWindow { 
             current: env.block.time.seconds(), 
             start: cfg.commit_window.start_time.unwrap_or(0), 
             end: cfg.commit_window.end_time.unwrap_or(u64::MAX) 
         });
     }
-    
-    COMMITS.save(deps.storage, info.sender.clone(), &CommitInfo { commitment: commitment.clone() })?;
-    Ok(Response::new().add_attributes(vec![attr("action", "commit"), attr("voter", info.sender), attr("commitment", commitment)]))
+    Ok(())
+}
+
+/// 保存本轮承诺，首次承诺的地址计入投票人数
+fn save_commit(storage: &mut dyn cosmwasm_std::Storage, round: u64, voter: &cosmwasm_std::Addr, commit: &CommitInfo) -> StdResult<()> {
+    if !COMMITS.has(storage, (round, voter)) {
+        let mut counts = VOTER_COUNTS.may_load(storage, round)?.unwrap_or_default();
+        counts.commits += 1;
+        VOTER_COUNTS.save(storage, round, &counts)?;
+    }
+    COMMITS.save(storage, (round, voter), commit)
+}
+
+/// 保存本轮揭示，首次揭示的地址计入揭示人数
+fn save_reveal(storage: &mut dyn cosmwasm_std::Storage, round: u64, voter: &cosmwasm_std::Addr, reveal: &RevealInfo) -> StdResult<()> {
+    if !REVEALS.has(storage, (round, voter)) {
+        let mut counts = VOTER_COUNTS.may_load(storage, round)?.unwrap_or_default();
+        counts.reveals += 1;
+        VOTER_COUNTS.save(storage, round, &counts)?;
+    }
+    REVEALS.save(storage, (round, voter), reveal)
 }
 
-/// 揭示：用 sha256(addr|reveal|salt) 与承诺比对，校验后记录揭示数据
+/// 揭示：按配置的承诺方案重算并与承诺比对，校验后记录揭示数据
 fn exec_reveal(deps: DepsMut, env: Env, info: MessageInfo, reveal: String, salt: String) -> Result<Response, ContractError> {
-    let cfg = CONFIG.load(deps.storage)?;
+    let cfg = load_config(deps.storage)?;
+    check_reveal_phase(&env, &cfg)?;
+    apply_reveal(deps.storage, &env, &cfg, &info.sender, &reveal, &salt)?;
+    Ok(Response::new()
+        .add_event(events::reveal_event(&info.sender))
+        .add_attributes(vec![attr("action", "reveal"), attr("voter", info.sender), attr("reveal", reveal)]))
+}
+
+/// 揭示阶段与揭示窗口校验
+fn check_reveal_phase(env: &Env, cfg: &Config) -> Result<(), ContractError> {
     if !matches!(cfg.vote_state, VoteState::Reveal) { return Err(ContractError::RevealNotActive); }
     
     // 验证是否在揭示窗口内
-    if !in_window(&env, &cfg.reveal_window) {
+    if !in_window(env, &cfg.reveal_window) {
         return Err(ContractError::OutsideWindow { 
             current: env.block.time.seconds(), 
             start: cfg.reveal_window.start_time.unwrap_or(0), 
             end: cfg.reveal_window.end_time.unwrap_or(u64::MAX) 
         });
     }
-    
-    let commit = COMMITS.may_load(deps.storage, info.sender.clone())?;
-    if commit.is_none() { return Err(ContractError::NothingToReveal); }
-    let c = commit.unwrap();
-    // verify: commitment == sha256(addr|reveal|salt) hex
-    let preimage = format!("{}|{}|{}", info.sender, reveal, salt);
-    let calc = sha2::Sha256::digest(preimage.as_bytes());
-    let calc_hex = hex::encode(calc);
+    Ok(())
+}
+
+/// 校验 voter 的承诺并记录揭示（本人揭示与委托揭示共用）
+fn apply_reveal(storage: &mut dyn cosmwasm_std::Storage, env: &Env, cfg: &Config, voter: &cosmwasm_std::Addr, reveal: &str, salt: &str) -> Result<(), ContractError> {
+    let c = COMMITS.may_load(storage, (cfg.round, voter))?.ok_or(ContractError::NothingToReveal)?;
+    let calc_hex = commitment::compute_commitment(cfg, env, voter.as_str(), reveal, salt);
     if calc_hex != c.commitment {
         return Err(ContractError::Std(cosmwasm_std::StdError::generic_err("commitment mismatch")));
     }
-    REVEALS.save(deps.storage, info.sender.clone(), &RevealInfo { reveal: reveal.clone(), salt: salt.clone() })?;
-    Ok(Response::new().add_attributes(vec![attr("action", "reveal"), attr("voter", info.sender), attr("reveal", reveal)]))
+    // 设有选票时校验选项并计票，reveal 仍作为结算随机熵保存
+    record_ballot(storage, cfg.round, voter, reveal)?;
+    save_reveal(storage, cfg.round, voter, &RevealInfo { reveal: reveal.to_string(), salt: salt.to_string() })?;
+    Ok(())
+}
+
+/// 投票者设置或撤销委托揭示方式
+fn exec_set_reveal_delegate(deps: DepsMut, info: MessageInfo, delegate: Option<RevealDelegateMsg>) -> Result<Response, ContractError> {
+    let delegate = match delegate {
+        None => {
+            REVEAL_DELEGATES.remove(deps.storage, &info.sender);
+            return Ok(Response::new().add_attributes(vec![attr("action", "set_reveal_delegate"), attr("voter", info.sender), attr("delegate", "none")]));
+        }
+        Some(RevealDelegateMsg::Relayer { address }) => RevealDelegate::Relayer { address: validate_address(&deps.as_ref(), &address)? },
+        Some(RevealDelegateMsg::Secp256k1 { pubkey }) => RevealDelegate::Secp256k1 { pubkey },
+        Some(RevealDelegateMsg::Ed25519 { pubkey }) => RevealDelegate::Ed25519 { pubkey },
+    };
+    let kind = match &delegate {
+        RevealDelegate::Relayer { .. } => "relayer",
+        RevealDelegate::Secp256k1 { .. } => "secp256k1",
+        RevealDelegate::Ed25519 { .. } => "ed25519",
+    };
+    REVEAL_DELEGATES.save(deps.storage, &info.sender, &delegate)?;
+    Ok(Response::new().add_attributes(vec![attr("action", "set_reveal_delegate"), attr("voter", info.sender), attr("delegate", kind)]))
+}
+
+/// 代投票者揭示：调用者须为授权的 relayer，或附带投票者公钥可验证的签名
+fn exec_reveal_for(deps: DepsMut, env: Env, info: MessageInfo, voter: String, reveal: String, salt: String, signature: Option<cosmwasm_std::Binary>) -> Result<Response, ContractError> {
+    let cfg = load_config(deps.storage)?;
+    check_reveal_phase(&env, &cfg)?;
+    let entry = SignedReveal { voter, reveal: reveal.clone(), salt, signature };
+    let voter = reveal_entry(deps, &env, &cfg, &info.sender, &entry)?;
+    Ok(Response::new()
+        .add_event(events::reveal_event(&voter))
+        .add_attributes(vec![attr("action", "reveal_for"), attr("voter", voter), attr("relayer", info.sender), attr("reveal", reveal)]))
+}
+
+/// 批量承诺：阶段与窗口对整批校验，各条目独立授权与保存，失败条目不影响其他条目
+fn exec_batch_commit(mut deps: DepsMut, env: Env, info: MessageInfo, entries: Vec<SignedCommit>) -> Result<Response, ContractError> {
+    let cfg = load_config(deps.storage)?;
+    check_commit_phase(&env, &cfg)?;
+    check_batch_size(entries.len())?;
+    let mut response = Response::new();
+    let mut results = vec![];
+    for entry in &entries {
+        let result = commit_entry(deps.branch(), &env, &cfg, &info.sender, entry);
+        if let Ok(voter) = &result {
+            response = response.add_event(events::commit_event(voter, &entry.commitment));
+        }
+        results.push(batch_result(&entry.voter, result));
+    }
+    batch_response(response, "batch_commit", &info.sender, results)
+}
+
+/// 批量揭示：阶段与窗口对整批校验，各条目独立授权与揭示，失败条目不影响其他条目
+fn exec_batch_reveal(mut deps: DepsMut, env: Env, info: MessageInfo, entries: Vec<SignedReveal>) -> Result<Response, ContractError> {
+    let cfg = load_config(deps.storage)?;
+    check_reveal_phase(&env, &cfg)?;
+    check_batch_size(entries.len())?;
+    let mut response = Response::new();
+    let mut results = vec![];
+    for entry in &entries {
+        let result = reveal_entry(deps.branch(), &env, &cfg, &info.sender, entry);
+        if let Ok(voter) = &result {
+            response = response.add_event(events::reveal_event(voter));
+        }
+        results.push(batch_result(&entry.voter, result));
+    }
+    batch_response(response, "batch_reveal", &info.sender, results)
+}
+
+fn check_batch_size(count: usize) -> Result<(), ContractError> {
+    if count > MAX_BATCH_ENTRIES {
+        return Err(ContractError::BatchTooLarge { count, max: MAX_BATCH_ENTRIES });
+    }
+    Ok(())
+}
+
+/// 校验并保存单条代发承诺，返回投票者地址
+///
+/// 承诺只接受投票者签名（relayer 委托仅用于揭示）；本轮已有不同承诺时拒绝，
+/// 避免重放旧的签名覆盖投票者之后提交的承诺
+fn commit_entry(deps: DepsMut, env: &Env, cfg: &Config, relayer: &cosmwasm_std::Addr, entry: &SignedCommit) -> Result<cosmwasm_std::Addr, ContractError> {
+    let voter = validate_address(&deps.as_ref(), &entry.voter)?;
+    let digest = commitment::commit_sign_digest(&env.block.chain_id, env.contract.address.as_str(), cfg.round, voter.as_str(), &entry.commitment);
+    authorize_delegate(deps.as_ref(), relayer, &voter, entry.signature.as_ref(), &digest, false)?;
+    if COMMITS.may_load(deps.storage, (cfg.round, &voter))?.is_some_and(|c| c.commitment != entry.commitment) {
+        return Err(ContractError::CommitmentExists);
+    }
+    save_commit(deps.storage, cfg.round, &voter, &CommitInfo { commitment: entry.commitment.clone() })?;
+    Ok(voter)
+}
+
+/// 校验并记录单条代发揭示，返回投票者地址（`RevealFor` 与 `BatchReveal` 共用）
+fn reveal_entry(deps: DepsMut, env: &Env, cfg: &Config, relayer: &cosmwasm_std::Addr, entry: &SignedReveal) -> Result<cosmwasm_std::Addr, ContractError> {
+    let voter = validate_address(&deps.as_ref(), &entry.voter)?;
+    let digest = commitment::reveal_sign_digest(&env.block.chain_id, env.contract.address.as_str(), cfg.round, voter.as_str(), &entry.reveal, &entry.salt);
+    authorize_delegate(deps.as_ref(), relayer, &voter, entry.signature.as_ref(), &digest, true)?;
+    apply_reveal(deps.storage, env, cfg, &voter, &entry.reveal, &entry.salt)?;
+    Ok(voter)
+}
+
+fn batch_result(voter: &str, result: Result<cosmwasm_std::Addr, ContractError>) -> BatchEntryResult {
+    match result {
+        Ok(voter) => BatchEntryResult { voter: voter.to_string(), success: true, error: None },
+        Err(err) => BatchEntryResult { voter: voter.to_string(), success: false, error: Some(err.to_string()) },
+    }
+}
+
+fn batch_response(response: Response, action: &str, relayer: &cosmwasm_std::Addr, results: Vec<BatchEntryResult>) -> Result<Response, ContractError> {
+    let succeeded = results.iter().filter(|r| r.success).count();
+    let failed = results.len() - succeeded;
+    Ok(response
+        .set_data(to_json_binary(&BatchResponse { results })?)
+        .add_attributes(vec![
+            attr("action", action),
+            attr("relayer", relayer.as_str()),
+            attr("succeeded", succeeded.to_string()),
+            attr("failed", failed.to_string()),
+        ]))
+}
+
+/// 校验代投票者操作的授权：relayer 须与登记地址一致（仅 `allow_relayer` 时接受）；公钥方式须对 digest 的签名有效
+fn authorize_delegate(deps: Deps, relayer: &cosmwasm_std::Addr, voter: &cosmwasm_std::Addr, signature: Option<&cosmwasm_std::Binary>, digest: &[u8; 32], allow_relayer: bool) -> Result<(), ContractError> {
+    let delegate = REVEAL_DELEGATES.may_load(deps.storage, voter)?.ok_or(ContractError::Unauthorized)?;
+    let verified = match (&delegate, signature) {
+        (RevealDelegate::Relayer { address }, _) => return if allow_relayer && address == relayer { Ok(()) } else { Err(ContractError::Unauthorized) },
+        (_, None) => return Err(ContractError::InvalidSignature),
+        (RevealDelegate::Secp256k1 { pubkey }, Some(sig)) => deps.api.secp256k1_verify(digest, sig, pubkey),
+        (RevealDelegate::Ed25519 { pubkey }, Some(sig)) => deps.api.ed25519_verify(digest, sig, pubkey),
+    };
+    match verified {
+        Ok(true) => Ok(()),
+        _ => Err(ContractError::InvalidSignature),
+    }
+}
+
+/// 全部揭示值的摘要：按地址顺序对 (地址, reveal, salt) 做长度前缀编码后取 sha256，
+/// 使揭晓种子依赖所有投票者的揭示而不仅是区块信息（salt 由投票者随机选取，是其中主要的熵）
+fn reveals_digest(storage: &dyn cosmwasm_std::Storage, round: u64) -> StdResult<[u8; 32]> {
+    let mut hasher = sha2::Sha256::new();
+    for item in REVEALS.prefix(round).range(storage, None, None, cosmwasm_std::Order::Ascending) {
+        let (addr, info) = item?;
+        for field in [addr.as_str(), info.reveal.as_str(), info.salt.as_str()] {
+            hasher.update((field.len() as u32).to_be_bytes());
+            hasher.update(field.as_bytes());
+        }
+    }
+    Ok(hasher.finalize().into())
+}
+
+/// 各档返还倍数（分子, 分母）：一等奖 2x、二等奖保本、三等奖 0.5x
+fn tier_multiplier(tier: u8) -> (u128, u128) {
+    match tier {
+        1 => (2, 1),
+        2 => (1, 1),
+        _ => (1, 2),
+    }
 }
 
 /// 结算：在 Closed 阶段与窗口内，使用 dd_algorithms_lib 进行三档抽样并转账返还
@@ -385,9 +1330,32 @@ fn exec_finalize(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response,
     if !matches!(cfg.vote_state, VoteState::Closed) {
         return Err(ContractError::InvalidState);
     }
+    if SETTLED_ROUND.may_load(deps.storage)? == Some(cfg.round) {
+        return Err(ContractError::AlreadySettled { round: cfg.round });
+    }
+
+    // 使用更安全的随机数种子，结合多个熵源
+    let seed = format!("{}{}{}{}", 
+        env.block.height, 
+        env.block.time.seconds(), 
+        env.contract.address,
+        env.transaction.as_ref().map(|t| t.index).unwrap_or(0)
+    );
+
+    // 首次结算时固定盲盒揭晓种子（区块熵与全部揭示值的哈希），之后不再改变
+    if let Some(mut plan) = REVEAL_PLAN.may_load(deps.storage)? {
+        if plan.seed.is_none() {
+            let mut hasher = sha2::Sha256::new();
+            hasher.update(seed.as_bytes());
+            hasher.update(reveals_digest(deps.storage, cfg.round)?);
+            plan.seed = Some(hex::encode(hasher.finalize()));
+            REVEAL_PLAN.save(deps.storage, &plan)?;
+        }
+    }
 
     // Build groups from commits and reveals to feed RNG: use simple mapping reveal strings -> u128 values
     let voters: Vec<_> = REVEALS
+        .prefix(cfg.round)
         .keys(deps.storage, None, None, cosmwasm_std::Order::Ascending)
         .filter_map(|a| a.ok())
         .collect();
@@ -409,16 +1377,8 @@ fn exec_finalize(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response,
     let mut group1: Vec<u128> = Vec::with_capacity(n);
     let mut group2: Vec<u128> = Vec::with_capacity(n);
 
-    // 使用更安全的随机数种子，结合多个熵源
-    let seed = format!("{}{}{}{}", 
-        env.block.height, 
-        env.block.time.seconds(), 
-        env.contract.address,
-        env.transaction.map(|t| t.index).unwrap_or(0)
-    );
-    
     for addr in &voters {
-        let r = REVEALS.load(deps.storage, addr.clone())?.reveal;
+        let r = REVEALS.load(deps.storage, (cfg.round, addr))?.reveal;
         // 使用更复杂的哈希函数和种子
         let combined = format!("{}{}{}", seed, addr, r);
         let hash = sha2::Sha256::digest(combined.as_bytes());
@@ -469,43 +1429,166 @@ fn exec_finalize(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response,
     }
 
     // 先完成所有状态更新，避免重入攻击
-    let mut payouts: Vec<(String, Uint128)> = vec![];
+    let mut payouts: Vec<(cosmwasm_std::Addr, Vec<Coin>)> = vec![];
+    let mut evts: Vec<cosmwasm_std::Event> = vec![];
+    let mut prize_mints: Vec<luckee_nft::msg::BatchMintItem> = vec![];
+    let mut next_prize_id = NEXT_PRIZE_TOKEN_ID.may_load(deps.storage)?.unwrap_or(PRIZE_TOKEN_ID_BASE);
     for (i, addr) in voters.iter().enumerate() {
-        let p = DEPOSITS.may_load(deps.storage, addr.clone())?.unwrap_or(Payout { principal: Uint128::zero() });
-        if p.principal.is_zero() { continue; }
-        let mult_num: u128;
-        let mult_den: u128;
-        let tier: u8;
-        if first_indices.contains(&i) { mult_num = 2; mult_den = 1; tier = 1; }
-        else if second_indices.contains(&i) { mult_num = 1; mult_den = 1; tier = 2; }
-        else { mult_num = 1; mult_den = 2; tier = 3; }
-        TIERS.save(deps.storage, addr.clone(), &tier)?;
-        let payout = p.principal * Uint128::from(mult_num) / Uint128::from(mult_den);
-        if !payout.is_zero() {
-            payouts.push((addr.to_string(), payout));
+        let principals = principals_of(deps.storage, &cfg, addr)?;
+        if principals.is_empty() { continue; }
+        let tier: u8 = if first_indices.contains(&i) { 1 } else if second_indices.contains(&i) { 2 } else { 3 };
+        let (mult_num, mult_den) = tier_multiplier(tier);
+        save_tier(deps.storage, cfg.round, addr, tier)?;
+        evts.push(events::tier_assigned_event(addr, tier));
+
+        // 若该档配置了奖品，则为中奖者铸造对应类型的 NFT
+        if let Some(mut prize) = TIER_PRIZES.may_load(deps.storage, tier)? {
+            prize_mints.push(luckee_nft::msg::BatchMintItem {
+                token_id: next_prize_id,
+                owner: addr.to_string(),
+                extension: luckee_nft::types::NftMeta {
+                    kind: prize.kind.clone(),
+                    scale_origin: to_nft_scale(&cfg.scale),
+                    physical_sku: None,
+                    crafted_from: None,
+                    series_id: prize.series_id.clone(),
+                    collection_group_id: Some(format!("prize_tier_{}", tier)),
+                    serial_in_series: prize.minted,
+                },
+            });
+            let mut owned = PRIZES.may_load(deps.storage, addr.clone())?.unwrap_or_default();
+            owned.push(PrizeInfo { token_id: next_prize_id, tier, kind: prize.kind.clone(), series_id: prize.series_id.clone() });
+            PRIZES.save(deps.storage, addr.clone(), &owned)?;
+            prize.minted += 1;
+            TIER_PRIZES.save(deps.storage, tier, &prize)?;
+            next_prize_id += 1;
+        }
+
+        // 各币种本金按同一倍数返还（BankMsg 要求币种有序且非零）
+        let mut payout: Vec<Coin> = principals.into_iter()
+            .map(|c| Coin { denom: c.denom, amount: c.amount * Uint128::from(mult_num) / Uint128::from(mult_den) })
+            .filter(|c| !c.amount.is_zero())
+            .collect();
+        payout.sort_by(|a, b| a.denom.cmp(&b.denom));
+        if !payout.is_empty() {
+            payouts.push((addr.clone(), payout));
+        }
+    }
+
+    // 没有持有本金的揭示者时不结算，本金继续计入应付款
+    if evts.is_empty() {
+        return Ok(Response::new().add_attribute("action", "finalize").add_attribute("note", "no voters"));
+    }
+
+    // 启用结算协议费时从每笔返还中扣除，按币种汇总后发送给 collector
+    let mut payout_fees: Vec<Coin> = vec![];
+    if cfg.protocol_fee.on_payouts {
+        for (_, amount) in payouts.iter_mut() {
+            for coin in amount.iter_mut() {
+                let fee = protocol_fee_of(&cfg, coin.amount);
+                if fee.is_zero() { continue; }
+                coin.amount -= fee;
+                match payout_fees.iter_mut().find(|c| c.denom == coin.denom) {
+                    Some(c) => c.amount += fee,
+                    None => payout_fees.push(Coin { denom: coin.denom.clone(), amount: fee }),
+                }
+            }
+            amount.retain(|c| !c.amount.is_zero());
         }
+        payouts.retain(|(_, amount)| !amount.is_empty());
+    }
+
+    // 返还已确定：结清截至目前的全部本金，剩余资金可由拥有者提取
+    for (addr, amount) in &payouts {
+        PAYOUTS.save(deps.storage, (cfg.round, addr), amount)?;
     }
+    let epoch = SETTLEMENT_EPOCH.may_load(deps.storage)?.unwrap_or(0) + 1;
+    SETTLEMENT_EPOCH.save(deps.storage, &epoch)?;
+    let totals = TOTAL_PRINCIPAL
+        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (denom, total) in totals {
+        RESOLVED_PRINCIPAL.save(deps.storage, &denom, &total)?;
+    }
+    SETTLED.save(deps.storage, &true)?;
+    SETTLED_ROUND.save(deps.storage, &cfg.round)?;
 
     // 最后构建发送消息，避免重入攻击
     let mut msgs: Vec<cosmwasm_std::CosmosMsg> = vec![];
     for (addr, amount) in payouts {
-        msgs.push(BankMsg::Send { 
-            to_address: addr, 
-            amount: vec![Coin { denom: cfg.base.denom.clone(), amount }] 
+        evts.push(events::payout_event(addr.as_str(), &amount));
+        // CW20 base 需单独发送 Transfer，其余原生币合并为一条银行转账
+        let (cw20_coins, native): (Vec<Coin>, Vec<Coin>) = amount.into_iter()
+            .partition(|c| cfg.cw20_token.as_ref().is_some_and(|t| c.denom == t.as_str()));
+        for coin in cw20_coins {
+            msgs.push(send_asset(&cfg, addr.as_str(), coin)?);
+        }
+        if !native.is_empty() {
+            msgs.push(BankMsg::Send { 
+                to_address: addr.to_string(), 
+                amount: native,
+            }.into());
+        }
+    }
+
+    let mut fee_total = Uint128::zero();
+    if let Some(collector) = &cfg.protocol_fee.collector {
+        for coin in payout_fees {
+            record_fee(deps.storage, &coin.denom, coin.amount)?;
+            fee_total += coin.amount;
+            msgs.push(send_asset(&cfg, collector.as_str(), coin)?);
+        }
+    }
+
+    let prizes_minted = prize_mints.len();
+    if !prize_mints.is_empty() {
+        let nft_contract = cfg.nft_contract.as_ref()
+            .ok_or_else(|| ContractError::Std(cosmwasm_std::StdError::generic_err("NFT contract not set")))?;
+        NEXT_PRIZE_TOKEN_ID.save(deps.storage, &next_prize_id)?;
+        msgs.push(cosmwasm_std::WasmMsg::Execute {
+            contract_addr: nft_contract.to_string(),
+            msg: to_json_binary(&luckee_nft::msg::ExecuteMsg::BatchMint { mints: prize_mints })?,
+            funds: vec![],
         }.into());
     }
 
-    Ok(Response::new().add_messages(msgs).add_attribute("action", "finalize"))
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_events(evts)
+        .add_attribute("action", "finalize")
+        .add_attribute("prizes_minted", prizes_minted.to_string())
+        .add_attribute("payout_fee", fee_total))
 }
 
 /// 查询入口：根据查询消息返回对应的序列化结果
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<cosmwasm_std::Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<cosmwasm_std::Binary> {
     match msg {
         QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::Status {} => to_json_binary(&query_status(deps, env)?),
         QueryMsg::DepositOf { address } => to_json_binary(&query_deposit(deps, address)?),
+        QueryMsg::CurrentPrice {} => to_json_binary(&query_current_price(deps, env)?),
+        QueryMsg::SaleStatus {} => to_json_binary(&query_sale_status(deps, env)?),
+        QueryMsg::Treasury {} => to_json_binary(&query_treasury(deps, env)?),
+        QueryMsg::Quote { denom, units } => to_json_binary(&query_quote(deps, env, denom, units)?),
+        QueryMsg::UserInfo { address } => to_json_binary(&query_user_info(deps, address)?),
         QueryMsg::TierOf { address } => to_json_binary(&query_tier(deps, address)?),
+        QueryMsg::PrizesOf { address } => to_json_binary(&query_prizes(deps, address)?),
+        QueryMsg::RevealStatus {} => to_json_binary(&query_reveal_status(deps)?),
+        QueryMsg::RevealedItem { token_id } => to_json_binary(&RevealedItemResponse { item: REVEALED_ITEMS.may_load(deps.storage, token_id)? }),
+        QueryMsg::PreviewMint { token_id } => to_json_binary(&query_preview_mint(deps, token_id)?),
         QueryMsg::OwnerOf { token_id } => to_json_binary(&query_owner_of(deps, token_id)?),
+        QueryMsg::ComputeCommitment { voter, reveal, salt } => to_json_binary(&query_compute_commitment(deps, env, voter, reveal, salt)?),
+        QueryMsg::TierCounts {} => to_json_binary(&query_tier_counts(deps)?),
         QueryMsg::TierList { tier, start_after, limit } => to_json_binary(&query_tier_list(deps, tier, start_after, limit)?),
+        QueryMsg::AllDeposits { start_after, limit } => to_json_binary(&query_all_deposits(deps, start_after, limit)?),
+        QueryMsg::AllCommits { start_after, limit } => to_json_binary(&query_all_commits(deps, start_after, limit)?),
+        QueryMsg::AllReveals { start_after, limit } => to_json_binary(&query_all_reveals(deps, start_after, limit)?),
+        QueryMsg::TopDepositors { limit } => to_json_binary(&query_top_depositors(deps, limit)?),
+        QueryMsg::VoteResults {} => to_json_binary(&query_vote_results(deps)?),
+        QueryMsg::RevealDelegate { voter } => {
+            let voter = deps.api.addr_validate(&voter)?;
+            to_json_binary(&RevealDelegateResponse { delegate: REVEAL_DELEGATES.may_load(deps.storage, &voter)? })
+        }
         QueryMsg::NftInfo { token_id } => to_json_binary(&query_nft_info(deps, token_id)?),
         QueryMsg::Approval { token_id } => to_json_binary(&query_approval(deps, token_id)?),
         QueryMsg::IsApprovedForAll { owner, operator } => to_json_binary(&query_is_approved_for_all(deps, owner, operator)?),
@@ -517,7 +1600,7 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<cosmwasm_std::Bi
 
 /// 查询全局配置（拥有者、总供应量、基础币、阶段、规模、NFT合约地址、NFT代码ID）
 fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
-    let cfg = CONFIG.load(deps.storage)?;
+    let cfg = load_config(deps.storage)?;
     Ok(ConfigResponse { 
         owner: cfg.owner.to_string(), 
         total_supply: cfg.total_supply, 
@@ -527,19 +1610,63 @@ fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
         first_prize_count: cfg.first_prize_count,
         nft_contract: cfg.nft_contract.map(|addr| addr.to_string()),
         nft_code_id: cfg.nft_code_id,
+        mint_template: cfg.mint_template,
+        pricing: cfg.pricing,
+        cw20_token: cfg.cw20_token.map(|addr| addr.to_string()),
+        sale_mode: cfg.sale_mode,
+        protocol_fee: cfg.protocol_fee,
+        paused: cfg.paused,
+        next_token_id: cfg.next_token_id,
+        commit_window: cfg.commit_window,
+        reveal_window: cfg.reveal_window,
+        closed_window: cfg.closed_window,
+        sale_window: cfg.sale_window,
+        commitment_scheme: cfg.commitment_scheme,
+        round: cfg.round,
     })
 }
 
+/// 运行状态：暂停标记、供应量、投票人数及各阶段窗口在当前区块是否生效
+fn query_status(deps: Deps, env: Env) -> StdResult<StatusResponse> {
+    let cfg = load_config(deps.storage)?;
+    let counts = VOTER_COUNTS.may_load(deps.storage, cfg.round)?.unwrap_or_default();
+    let window = |w: &PhaseWindow| WindowStatus { window: w.clone(), active: in_window(&env, w) };
+    Ok(StatusResponse {
+        paused: cfg.paused,
+        vote_state: cfg.vote_state.clone(),
+        minted: cfg.next_token_id,
+        total_supply: cfg.total_supply,
+        remaining: cfg.total_supply.saturating_sub(cfg.next_token_id),
+        commits: counts.commits,
+        reveals: counts.reveals,
+        settled: SETTLED.may_load(deps.storage)?.unwrap_or(false),
+        commit_window: window(&cfg.commit_window),
+        reveal_window: window(&cfg.reveal_window),
+        closed_window: window(&cfg.closed_window),
+        sale_window: window(&cfg.sale_window),
+    })
+}
+
+/// 预览按当前模板铸造指定 token_id 时的元数据
+fn query_preview_mint(deps: Deps, token_id: u64) -> StdResult<PreviewMintResponse> {
+    let cfg = load_config(deps.storage)?;
+    Ok(PreviewMintResponse { token_id, extension: build_nft_meta(&cfg, token_id) })
+}
+
 /// 处理子消息回调
-pub fn reply(deps: DepsMut, _env: Env, msg: SubMsgResponse) -> Result<Response, ContractError> {
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    if msg.id != INSTANTIATE_NFT_REPLY_ID {
+        return Err(ContractError::Std(cosmwasm_std::StdError::generic_err(format!("unknown reply id {}", msg.id))));
+    }
+    let response = msg.result.into_result().map_err(|e| ContractError::Std(cosmwasm_std::StdError::generic_err(e)))?;
     // 检查是否是NFT合约实例化成功的回调
-    if let Some(event) = msg.events.iter().find(|e| e.ty == "instantiate") {
+    if let Some(event) = response.events.iter().find(|e| e.ty == "instantiate") {
         if let Some(contract_address) = event.attributes.iter()
             .find(|attr| attr.key == "_contract_address")
             .map(|attr| &attr.value) {
             
             // 保存NFT合约地址到配置中
-            let mut cfg = CONFIG.load(deps.storage)?;
+            let mut cfg = load_config(deps.storage)?;
             cfg.nft_contract = Some(deps.api.addr_validate(contract_address)?);
             CONFIG.save(deps.storage, &cfg)?;
             
@@ -552,43 +1679,205 @@ pub fn reply(deps: DepsMut, _env: Env, msg: SubMsgResponse) -> Result<Response,
     Ok(Response::new().add_attribute("action", "reply_success"))
 }
 
-/// 迁移：空置实现，为未来升级预留
-pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
-    // 空置实现，当前不需要任何迁移逻辑
-    // 为未来可能的合约升级预留接口
-    
-    // 确保合约版本已更新（这是迁移的基本要求）
+/// 迁移：校验合约名称与版本（拒绝跨合约迁移与降级），按顺序执行 `migrations::STEPS`
+/// 中尚未应用的存储步骤，再应用 `MigrateMsg` 携带的可选配置参数并更新合约版本
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    // 拒绝跨合约迁移与降级
+    let previous = get_contract_version(deps.storage)?;
+    if previous.contract != CONTRACT_NAME {
+        return Err(ContractError::InvalidContractName { previous: previous.contract });
+    }
+    if parse_version(&previous.version) > parse_version(CONTRACT_VERSION) {
+        return Err(ContractError::CannotDowngrade { previous: previous.version, current: CONTRACT_VERSION.to_string() });
+    }
+
+    // 按顺序执行未应用的存储迁移步骤
+    let applied = migrations::run_pending(deps.storage)?;
+
+    // 应用可选的配置参数
+    let mut cfg = load_config(deps.storage)?;
+    let mut changed = vec![];
+    if let Some(owner) = msg.owner {
+        cfg.owner = validate_address(&deps.as_ref(), &owner)?;
+        changed.push("owner");
+    }
+    if let Some(paused) = msg.paused {
+        cfg.paused = paused;
+        changed.push("paused");
+    }
+    if let Some(first_prize_count) = msg.first_prize_count {
+        cfg.first_prize_count = first_prize_count;
+        changed.push("first_prize_count");
+    }
+    if let Some(sale_window) = msg.sale_window {
+        cfg.sale_window = sale_window;
+        changed.push("sale_window");
+    }
+    if !changed.is_empty() {
+        CONFIG.save(deps.storage, &cfg)?;
+    }
+
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
-    
+
+    let message = if applied.is_empty() && changed.is_empty() {
+        "Migration completed - no changes applied".to_string()
+    } else {
+        format!("Migration completed - {} steps applied, {} config fields updated", applied.len(), changed.len())
+    };
     Ok(Response::new()
         .add_attribute("action", "migrate")
-        .add_attribute("message", "Migration completed - no changes applied"))
+        .add_attribute("message", message)
+        .add_attribute("from_version", previous.version)
+        .add_attribute("to_version", CONTRACT_VERSION)
+        .add_attribute("steps", applied.join(","))
+        .add_attribute("config_fields", changed.join(",")))
 }
 
-/// 查询指定地址的累计充值本金
+/// 解析 `major.minor.patch` 版本号（忽略预发布后缀），用于比较新旧版本
+fn parse_version(version: &str) -> (u64, u64, u64) {
+    let core = version.split(['-', '+']).next().unwrap_or_default();
+    let mut parts = core.split('.').map(|p| p.parse::<u64>().unwrap_or(0));
+    (parts.next().unwrap_or(0), parts.next().unwrap_or(0), parts.next().unwrap_or(0))
+}
+
+/// 查询指定地址的累计充值本金（base 币种及其他币种）
 fn query_deposit(deps: Deps, address: String) -> StdResult<DepositResponse> {
     let addr = deps.api.addr_validate(&address)?;
-    let p = DEPOSITS.may_load(deps.storage, addr)?.unwrap_or(Payout { principal: Uint128::zero() });
-    Ok(DepositResponse { principal: p.principal.to_string() })
+    let p = DEPOSITS.may_load(deps.storage, addr.clone())?.unwrap_or(Payout { principal: Uint128::zero() });
+    let entry = deposit_entry(deps, addr, p.principal)?;
+    Ok(DepositResponse { principal: entry.principal.to_string(), others: entry.others })
+}
+
+/// 金库：base 及价格表中各币种的余额、应付、可提取与已提取金额
+fn query_treasury(deps: Deps, env: Env) -> StdResult<TreasuryResponse> {
+    let cfg = load_config(deps.storage)?;
+    let mut balances = vec![treasury_balance(deps, &env, &cfg, &cfg.base.denom)?];
+    for coin in &cfg.pricing.extra_prices {
+        balances.push(treasury_balance(deps, &env, &cfg, &coin.denom)?);
+    }
+    Ok(TreasuryResponse {
+        settled: SETTLED.may_load(deps.storage)?.unwrap_or(false),
+        balances,
+        revenue_split: cfg.revenue_split.iter()
+            .map(|s| RevenueShareMsg { address: s.address.to_string(), share_bps: s.share_bps })
+            .collect(),
+    })
+}
+
+/// 发售状态：未暂停、处于发售窗口且尚有剩余时视为开放
+fn query_sale_status(deps: Deps, env: Env) -> StdResult<SaleStatusResponse> {
+    let cfg = load_config(deps.storage)?;
+    let remaining = cfg.total_supply.saturating_sub(cfg.next_token_id);
+    Ok(SaleStatusResponse {
+        open: !cfg.paused && remaining > 0 && in_window(&env, &cfg.sale_window),
+        sold: cfg.next_token_id,
+        remaining,
+        ends_at_height: cfg.sale_window.end_height,
+        ends_at_time: cfg.sale_window.end_time,
+    })
+}
+
+/// 当前价格：下一个 token 在当前区块的 base 单价与最近成交价
+fn query_current_price(deps: Deps, env: Env) -> StdResult<CurrentPriceResponse> {
+    let cfg = load_config(deps.storage)?;
+    let price = unit_price(&cfg, &env, &cfg.base.denom).unwrap_or_default();
+    let amount = token_price(&cfg, &cfg.base.denom, price, cfg.next_token_id)?;
+    Ok(CurrentPriceResponse {
+        sale_mode: cfg.sale_mode.clone(),
+        price: Coin { denom: cfg.base.denom.clone(), amount },
+        last_clearing_price: LAST_CLEARING_PRICE.may_load(deps.storage)?,
+    })
+}
+
+/// 报价：从当前 next_token_id 起以指定币种购买 units 个的总价
+fn query_quote(deps: Deps, env: Env, denom: String, units: u64) -> StdResult<QuoteResponse> {
+    let cfg = load_config(deps.storage)?;
+    let price = unit_price(&cfg, &env, &denom)
+        .ok_or_else(|| cosmwasm_std::StdError::generic_err(format!("denom '{}' not accepted", denom)))?;
+    let remaining = cfg.total_supply.saturating_sub(cfg.next_token_id);
+    if units > remaining {
+        return Err(cosmwasm_std::StdError::generic_err(format!("only {} units remaining", remaining)));
+    }
+    let amount = quote_cost(&cfg, &denom, price, cfg.next_token_id, units)?;
+    Ok(QuoteResponse { units, cost: Coin { denom, amount } })
 }
 
-/// 查询指定地址的分层结果（1/2/3，未设置返回 0）
+/// 查询指定地址在当前轮次的分层结果（1/2/3，未设置返回 0）
 fn query_tier(deps: Deps, address: String) -> StdResult<TierResponse> {
     let addr = deps.api.addr_validate(&address)?;
-    let t = TIERS.may_load(deps.storage, addr)?.unwrap_or(0);
+    let round = load_config(deps.storage)?.round;
+    let t = TIERS.may_load(deps.storage, (round, &addr))?.unwrap_or(0);
     Ok(TierResponse { tier: t })
 }
 
+/// 用户面板：本金、购买记录、投票状态、档位与返还情况
+fn query_user_info(deps: Deps, address: String) -> StdResult<UserInfoResponse> {
+    let cfg = load_config(deps.storage)?;
+    let addr = deps.api.addr_validate(&address)?;
+    let principal = principals_of(deps.storage, &cfg, &addr)?;
+    let purchases = PURCHASES.prefix(&addr)
+        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .map(|item| item.map(|(first_token_id, count)| TokenRange { first_token_id, count }))
+        .collect::<StdResult<Vec<_>>>()?;
+    let revealed = REVEALS.has(deps.storage, (cfg.round, &addr));
+    let tier = TIERS.may_load(deps.storage, (cfg.round, &addr))?;
+    let paid = PAYOUTS.may_load(deps.storage, (cfg.round, &addr))?;
+
+    let payout = match (&paid, tier) {
+        (Some(paid), _) => paid.clone(),
+        (None, Some(tier)) => {
+            let (num, den) = tier_multiplier(tier);
+            principal.iter()
+                .map(|c| {
+                    let gross = c.amount.multiply_ratio(num, den);
+                    let fee = if cfg.protocol_fee.on_payouts { protocol_fee_of(&cfg, gross) } else { Uint128::zero() };
+                    Coin { denom: c.denom.clone(), amount: gross - fee }
+                })
+                .filter(|c| !c.amount.is_zero())
+                .collect()
+        }
+        (None, None) => vec![],
+    };
+
+    Ok(UserInfoResponse {
+        units_purchased: purchases.iter().map(|p| p.count).sum(),
+        purchases,
+        committed: COMMITS.has(deps.storage, (cfg.round, &addr)),
+        revealed,
+        tier,
+        payout,
+        paid_out: paid.is_some(),
+        refund_eligible: revealed && !principal.is_empty(),
+        principal,
+    })
+}
+
+/// 查询指定地址在结算中获得的奖品 NFT
+fn query_prizes(deps: Deps, address: String) -> StdResult<PrizesResponse> {
+    let addr = deps.api.addr_validate(&address)?;
+    let prizes = PRIZES.may_load(deps.storage, addr)?.unwrap_or_default();
+    Ok(PrizesResponse { prizes })
+}
+
+/// 查询盲盒揭晓状态
+fn query_reveal_status(deps: Deps) -> StdResult<RevealStatusResponse> {
+    Ok(match REVEAL_PLAN.may_load(deps.storage)? {
+        Some(plan) => RevealStatusResponse { distribution_hash: Some(plan.distribution_hash), seed: plan.seed, verified: plan.verified, revealed: plan.revealed, revealed_count: plan.revealed_count },
+        None => RevealStatusResponse { distribution_hash: None, seed: None, verified: false, revealed: false, revealed_count: 0 },
+    })
+}
+
 // 移除不再使用的is_operator函数，因为NFT操作现在通过外部合约处理
 
 /// 转移 NFT：通过外部NFT合约执行
 fn exec_transfer(deps: DepsMut, _info: MessageInfo, recipient: String, token_id: u64) -> Result<Response, ContractError> {
-    let cfg = CONFIG.load(deps.storage)?;
+    let cfg = load_config(deps.storage)?;
+    let recipient = validate_address(&deps.as_ref(), &recipient)?;
     let nft_contract = cfg.nft_contract.as_ref()
         .ok_or_else(|| ContractError::Std(cosmwasm_std::StdError::generic_err("NFT contract not set")))?;
 
     let transfer_msg = luckee_nft::msg::ExecuteMsg::TransferNft { 
-        recipient: recipient.clone(), 
+        recipient: recipient.to_string(), 
         token_id 
     };
     let transfer_msg_binary = cosmwasm_std::to_json_binary(&transfer_msg)?;
@@ -610,12 +1899,13 @@ fn exec_transfer(deps: DepsMut, _info: MessageInfo, recipient: String, token_id:
 
 /// 授权某地址对单个 NFT 的转移权限：通过外部NFT合约执行
 fn exec_approve(deps: DepsMut, _info: MessageInfo, spender: String, token_id: u64) -> Result<Response, ContractError> {
-    let cfg = CONFIG.load(deps.storage)?;
+    let cfg = load_config(deps.storage)?;
+    let spender = validate_address(&deps.as_ref(), &spender)?;
     let nft_contract = cfg.nft_contract.as_ref()
         .ok_or_else(|| ContractError::Std(cosmwasm_std::StdError::generic_err("NFT contract not set")))?;
 
     let approve_msg = luckee_nft::msg::ExecuteMsg::Approve { 
-        spender: spender.clone(), 
+        spender: spender.to_string(), 
         token_id,
         expires: None,
     };
@@ -638,12 +1928,13 @@ fn exec_approve(deps: DepsMut, _info: MessageInfo, spender: String, token_id: u6
 
 /// 撤销单个 NFT 的授权：通过外部NFT合约执行
 fn exec_revoke(deps: DepsMut, _info: MessageInfo, spender: String, token_id: u64) -> Result<Response, ContractError> {
-    let cfg = CONFIG.load(deps.storage)?;
+    let cfg = load_config(deps.storage)?;
+    let spender = validate_address(&deps.as_ref(), &spender)?;
     let nft_contract = cfg.nft_contract.as_ref()
         .ok_or_else(|| ContractError::Std(cosmwasm_std::StdError::generic_err("NFT contract not set")))?;
 
     let revoke_msg = luckee_nft::msg::ExecuteMsg::Revoke { 
-        spender: spender.clone(), 
+        spender: spender.to_string(), 
         token_id 
     };
     let revoke_msg_binary = cosmwasm_std::to_json_binary(&revoke_msg)?;
@@ -665,12 +1956,13 @@ fn exec_revoke(deps: DepsMut, _info: MessageInfo, spender: String, token_id: u64
 
 /// 设置全局操作员（对所有 NFT 有操作权限）：通过外部NFT合约执行
 fn exec_approve_all(deps: DepsMut, info: MessageInfo, operator: String) -> Result<Response, ContractError> {
-    let cfg = CONFIG.load(deps.storage)?;
+    let cfg = load_config(deps.storage)?;
+    let operator = validate_address(&deps.as_ref(), &operator)?;
     let nft_contract = cfg.nft_contract.as_ref()
         .ok_or_else(|| ContractError::Std(cosmwasm_std::StdError::generic_err("NFT contract not set")))?;
 
     let approve_all_msg = luckee_nft::msg::ExecuteMsg::ApproveAll { 
-        operator: operator.clone(),
+        operator: operator.to_string(),
         expires: None,
     };
     let approve_all_msg_binary = cosmwasm_std::to_json_binary(&approve_all_msg)?;
@@ -692,12 +1984,13 @@ fn exec_approve_all(deps: DepsMut, info: MessageInfo, operator: String) -> Resul
 
 /// 取消全局操作员：通过外部NFT合约执行
 fn exec_revoke_all(deps: DepsMut, info: MessageInfo, operator: String) -> Result<Response, ContractError> {
-    let cfg = CONFIG.load(deps.storage)?;
+    let cfg = load_config(deps.storage)?;
+    let operator = validate_address(&deps.as_ref(), &operator)?;
     let nft_contract = cfg.nft_contract.as_ref()
         .ok_or_else(|| ContractError::Std(cosmwasm_std::StdError::generic_err("NFT contract not set")))?;
 
     let revoke_all_msg = luckee_nft::msg::ExecuteMsg::RevokeAll { 
-        operator: operator.clone(),
+        operator: operator.to_string(),
     };
     let revoke_all_msg_binary = cosmwasm_std::to_json_binary(&revoke_all_msg)?;
     
@@ -718,7 +2011,7 @@ fn exec_revoke_all(deps: DepsMut, info: MessageInfo, operator: String) -> Result
 
 /// 查询 NFT 信息：提示用户直接查询NFT合约
 fn query_nft_info(deps: Deps, token_id: u64) -> StdResult<NftInfoResponse> {
-    let cfg = CONFIG.load(deps.storage)?;
+    let cfg = load_config(deps.storage)?;
     if let Some(nft_contract) = cfg.nft_contract {
         Err(cosmwasm_std::StdError::generic_err(format!(
             "Please query NFT contract directly at {} for token_id {}", 
@@ -731,7 +2024,7 @@ fn query_nft_info(deps: Deps, token_id: u64) -> StdResult<NftInfoResponse> {
 
 /// 查询单次授权：提示用户直接查询NFT合约
 fn query_approval(deps: Deps, token_id: u64) -> StdResult<ApprovalResponse> {
-    let cfg = CONFIG.load(deps.storage)?;
+    let cfg = load_config(deps.storage)?;
     if let Some(nft_contract) = cfg.nft_contract {
         Err(cosmwasm_std::StdError::generic_err(format!(
             "Please query NFT contract directly at {} for token_id {}", 
@@ -744,7 +2037,9 @@ fn query_approval(deps: Deps, token_id: u64) -> StdResult<ApprovalResponse> {
 
 /// 查询是否设置了全局操作员：提示用户直接查询NFT合约
 fn query_is_approved_for_all(deps: Deps, owner: String, operator: String) -> StdResult<IsApprovedForAllResponse> {
-    let cfg = CONFIG.load(deps.storage)?;
+    let cfg = load_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&owner)?;
+    let operator = deps.api.addr_validate(&operator)?;
     if let Some(nft_contract) = cfg.nft_contract {
         Err(cosmwasm_std::StdError::generic_err(format!(
             "Please query NFT contract directly at {} for owner {} and operator {}", 
@@ -757,7 +2052,7 @@ fn query_is_approved_for_all(deps: Deps, owner: String, operator: String) -> Std
 
 /// 查询 NFT 所有者：提示用户直接查询NFT合约
 fn query_owner_of(deps: Deps, token_id: u64) -> StdResult<OwnerOfResponse> {
-    let cfg = CONFIG.load(deps.storage)?;
+    let cfg = load_config(deps.storage)?;
     if let Some(nft_contract) = cfg.nft_contract {
         Err(cosmwasm_std::StdError::generic_err(format!(
             "Please query NFT contract directly at {} for token_id {}", 
@@ -768,45 +2063,141 @@ fn query_owner_of(deps: Deps, token_id: u64) -> StdResult<OwnerOfResponse> {
     }
 }
 
-/// 查询指定分层的地址列表（支持分页）
+/// 查询当前轮次指定分层的地址列表（支持分页）
 fn query_tier_list(deps: Deps, tier: u8, start_after: Option<String>, limit: Option<u32>) -> StdResult<TierListResponse> {
-    let start = if let Some(sa) = start_after { 
-        Some(validate_address(&deps, &sa).map_err(|e| cosmwasm_std::StdError::generic_err(e.to_string()))?) 
-    } else { 
-        None 
-    };
-    let take = limit.unwrap_or(50) as usize;
-    let mut addrs: Vec<String> = Vec::with_capacity(take);
-    let mut next: Option<String> = None;
-    let iter = TIERS.range(deps.storage, None, None, cosmwasm_std::Order::Ascending);
-    let mut passed = start.is_none();
-    // let mut found_count = 0;
-    
-    for item in iter {
-        let (addr, t) = item?;
-        if !passed {
-            if Some(addr.clone()) == start { 
-                passed = true; 
-                continue; // 跳过起始地址
-            } else { 
-                continue; 
-            }
-        }
-        if t == tier {
-            if addrs.len() < take { 
-                addrs.push(addr.to_string()); 
-            } else { 
-                next = Some(addr.to_string()); 
-                break; 
+    let (start, take) = page_args(deps, start_after, limit)?;
+    let round = load_config(deps.storage)?.round;
+    // 按 (轮次, 档位, 地址) 索引范围读取，多取一个用于判断是否还有下一页
+    let mut addrs: Vec<String> = TIER_INDEX
+        .prefix((round, tier))
+        .keys(deps.storage, start.as_ref().map(cw_storage_plus::Bound::exclusive), None, cosmwasm_std::Order::Ascending)
+        .take(take + 1)
+        .map(|k| k.map(|addr| addr.to_string()))
+        .collect::<StdResult<Vec<_>>>()?;
+    let next = if addrs.len() > take { addrs.pop() } else { None };
+    Ok(TierListResponse { addresses: addrs, next_start_after: next })
+}
+
+/// 按当前承诺方案与轮次计算 voter 的承诺
+fn query_compute_commitment(deps: Deps, env: Env, voter: String, reveal: String, salt: String) -> StdResult<ComputeCommitmentResponse> {
+    let cfg = load_config(deps.storage)?;
+    let voter = deps.api.addr_validate(&voter)?;
+    let commitment = commitment::compute_commitment(&cfg, &env, voter.as_str(), &reveal, &salt);
+    Ok(ComputeCommitmentResponse { scheme: cfg.commitment_scheme, round: cfg.round, commitment })
+}
+
+/// 当前轮次各档位地址数量（由 TIER_COUNTS 计数，无需遍历）
+fn query_tier_counts(deps: Deps) -> StdResult<TierCountsResponse> {
+    let round = load_config(deps.storage)?.round;
+    let count = |tier: u8| -> StdResult<u64> { Ok(TIER_COUNTS.may_load(deps.storage, (round, tier))?.unwrap_or_default()) };
+    Ok(TierCountsResponse { first: count(1)?, second: count(2)?, third: count(3)? })
+}
+
+/// 解析分页参数：起始地址（不含）与截断后的数量
+fn page_args(deps: Deps, start_after: Option<String>, limit: Option<u32>) -> StdResult<(Option<cosmwasm_std::Addr>, usize)> {
+    let start = start_after
+        .map(|sa| validate_address(&deps, &sa).map_err(|e| cosmwasm_std::StdError::generic_err(e.to_string())))
+        .transpose()?;
+    Ok((start, limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize))
+}
+
+/// 地址的充值记录（base 本金及其他币种本金）
+fn deposit_entry(deps: Deps, addr: cosmwasm_std::Addr, principal: Uint128) -> StdResult<DepositEntry> {
+    let others = EXTRA_DEPOSITS.prefix(&addr)
+        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .map(|item| item.map(|(denom, amount)| Coin { denom, amount }))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(DepositEntry { address: addr.to_string(), principal, others })
+}
+
+/// 按地址分页列出充值记录
+fn query_all_deposits(deps: Deps, start_after: Option<String>, limit: Option<u32>) -> StdResult<AllDepositsResponse> {
+    let (start, take) = page_args(deps, start_after, limit)?;
+    // 合并 DEPOSITS 与 EXTRA_DEPOSITS 的地址（均按地址升序），仅以其他币种充值的地址也会列出
+    let mut addrs = DEPOSITS
+        .keys(deps.storage, start.clone().map(cw_storage_plus::Bound::exclusive), None, cosmwasm_std::Order::Ascending)
+        .take(take)
+        .collect::<StdResult<Vec<_>>>()?;
+    let mut extra: Vec<cosmwasm_std::Addr> = Vec::with_capacity(take);
+    for item in EXTRA_DEPOSITS.prefix_range(deps.storage, start.as_ref().map(cw_storage_plus::PrefixBound::exclusive), None, cosmwasm_std::Order::Ascending) {
+        let ((addr, _), _) = item?;
+        if extra.last() != Some(&addr) {
+            if extra.len() == take {
+                break;
             }
+            extra.push(addr);
         }
     }
-    Ok(TierListResponse { addresses: addrs, next_start_after: next })
+    addrs.extend(extra);
+    addrs.sort();
+    addrs.dedup();
+    addrs.truncate(take);
+    let deposits = addrs
+        .into_iter()
+        .map(|addr| {
+            let principal = DEPOSITS.may_load(deps.storage, addr.clone())?.map(|p| p.principal).unwrap_or_default();
+            deposit_entry(deps, addr, principal)
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(AllDepositsResponse { deposits })
+}
+
+/// 按地址分页列出当前轮次的承诺
+fn query_all_commits(deps: Deps, start_after: Option<String>, limit: Option<u32>) -> StdResult<AllCommitsResponse> {
+    let (start, take) = page_args(deps, start_after, limit)?;
+    let round = load_config(deps.storage)?.round;
+    let commits = COMMITS
+        .prefix(round)
+        .range(deps.storage, start.as_ref().map(cw_storage_plus::Bound::exclusive), None, cosmwasm_std::Order::Ascending)
+        .take(take)
+        .map(|item| item.map(|(addr, c)| CommitEntry { address: addr.to_string(), commitment: c.commitment }))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(AllCommitsResponse { commits })
+}
+
+/// 按地址分页列出当前轮次的揭示
+fn query_all_reveals(deps: Deps, start_after: Option<String>, limit: Option<u32>) -> StdResult<AllRevealsResponse> {
+    let (start, take) = page_args(deps, start_after, limit)?;
+    let round = load_config(deps.storage)?.round;
+    let reveals = REVEALS
+        .prefix(round)
+        .range(deps.storage, start.as_ref().map(cw_storage_plus::Bound::exclusive), None, cosmwasm_std::Order::Ascending)
+        .take(take)
+        .map(|item| item.map(|(addr, r)| RevealEntry { address: addr.to_string(), reveal: r.reveal, salt: r.salt }))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(AllRevealsResponse { reveals })
+}
+
+/// base 本金从高到低的前 limit 个地址（由 DEPOSIT_RANK 倒序读取）
+fn query_top_depositors(deps: Deps, limit: Option<u32>) -> StdResult<TopDepositorsResponse> {
+    let (_, take) = page_args(deps, None, limit)?;
+    let depositors = DEPOSIT_RANK
+        .keys(deps.storage, None, None, cosmwasm_std::Order::Descending)
+        .take(take)
+        .map(|item| item.and_then(|(principal, addr)| deposit_entry(deps, addr, Uint128::from(principal))))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(TopDepositorsResponse { denom: load_config(deps.storage)?.base.denom, depositors })
+}
+
+/// 当前轮次各选项的票数
+fn query_vote_results(deps: Deps) -> StdResult<VoteResultsResponse> {
+    let cfg = load_config(deps.storage)?;
+    let options = BALLOT.may_load(deps.storage)?.unwrap_or_default()
+        .into_iter()
+        .enumerate()
+        .map(|(i, label)| {
+            let index = i as u32;
+            let votes = VOTE_TALLY.may_load(deps.storage, (cfg.round, index))?.unwrap_or_default();
+            Ok(OptionTally { index, label, votes })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    let total_votes = options.iter().map(|o| o.votes).sum();
+    Ok(VoteResultsResponse { round: cfg.round, options, total_votes })
 }
 
 /// 查询Token URI：提示用户直接查询NFT合约
 fn query_token_uri(deps: Deps, token_id: u64) -> StdResult<crate::msg::TokenUriResponse> {
-    let cfg = CONFIG.load(deps.storage)?;
+    let cfg = load_config(deps.storage)?;
     if let Some(nft_contract) = cfg.nft_contract {
         Err(cosmwasm_std::StdError::generic_err(format!(
             "Please query NFT contract directly at {} for token_id {}", 
@@ -819,7 +2210,7 @@ fn query_token_uri(deps: Deps, token_id: u64) -> StdResult<crate::msg::TokenUriR
 
 /// 查询所有Token ID列表：提示用户直接查询NFT合约
 fn query_all_tokens(deps: Deps, _start_after: Option<u64>, _limit: Option<u32>) -> StdResult<crate::msg::AllTokensResponse> {
-    let cfg = CONFIG.load(deps.storage)?;
+    let cfg = load_config(deps.storage)?;
     if let Some(nft_contract) = cfg.nft_contract {
         Err(cosmwasm_std::StdError::generic_err(format!(
             "Please query NFT contract directly at {} for all tokens", 
@@ -832,7 +2223,8 @@ fn query_all_tokens(deps: Deps, _start_after: Option<u64>, _limit: Option<u32>)
 
 /// 查询指定用户拥有的Token ID列表：提示用户直接查询NFT合约
 fn query_tokens(deps: Deps, owner: String, _start_after: Option<u64>, _limit: Option<u32>) -> StdResult<crate::msg::TokensResponse> {
-    let cfg = CONFIG.load(deps.storage)?;
+    let cfg = load_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&owner)?;
     if let Some(nft_contract) = cfg.nft_contract {
         Err(cosmwasm_std::StdError::generic_err(format!(
             "Please query NFT contract directly at {} for owner {}", 
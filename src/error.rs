@@ -31,8 +31,50 @@ pub enum ContractError {
     #[error("Outside time window: current {current}, window {start}-{end}")]
     OutsideWindow { current: u64, start: u64, end: u64 },
 
+    #[error("Invalid total supply: {total_supply} (allowed {min}-{max})")]
+    InvalidTotalSupply { total_supply: u64, min: u64, max: u64 },
+
+    #[error("Invalid tier: {tier} (expected 1, 2 or 3)")]
+    InvalidTier { tier: u8 },
+
+    #[error("Insufficient proceeds for {denom}: requested {requested}, available {available}")]
+    InsufficientProceeds { denom: String, requested: cosmwasm_std::Uint128, available: cosmwasm_std::Uint128 },
+
+    #[error("Cannot migrate from contract {previous}")]
+    InvalidContractName { previous: String },
+
+    #[error("Cannot migrate from version {previous} to older version {current}")]
+    CannotDowngrade { previous: String, current: String },
+
+    #[error("NFT contract cannot be changed after minting has started")]
+    NftContractLocked,
+
+    #[error("Invalid NFT contract {address}: {reason}")]
+    InvalidNftContract { address: String, reason: String },
+
+    #[error("Invalid choice {choice}: expected an option index below {options}")]
+    InvalidChoice { choice: String, options: usize },
+
+    #[error("Invalid delegate signature")]
+    InvalidSignature,
+
+    #[error("Voter already committed a different commitment this round")]
+    CommitmentExists,
+
+    #[error("Batch too large: {count} entries exceeds maximum {max}")]
+    BatchTooLarge { count: usize, max: usize },
+
     #[error("Too many voters: {count} exceeds maximum {max}")]
     TooManyVoters { count: usize, max: usize },
+
+    #[error("Round {round} is already settled")]
+    AlreadySettled { round: u64 },
+
+    #[error("Sale is closed: the blind box reveal seed is already fixed")]
+    RevealSeedFixed,
+
+    #[error("Deposit must carry a single coin, got {count}")]
+    MultipleCoins { count: usize },
 }
 
 
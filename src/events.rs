@@ -0,0 +1,102 @@
+//! 面向链下索引器的结构化事件
+//!
+//! CosmWasm 会为合约事件类型加上 `wasm-` 前缀，例如 `blind_box_deposit` 在链上显示为
+//! `wasm-blind_box_deposit`。各事件的属性键保持稳定，结构变化时递增 `EVENT_SCHEMA_VERSION`。
+use cosmwasm_std::{Addr, Coin, Event, Uint128};
+
+use crate::state::VoteState;
+
+/// 事件结构版本，写入每个事件的 `version` 属性
+pub const EVENT_SCHEMA_VERSION: &str = "1";
+
+/// 充值并铸造
+pub const DEPOSIT: &str = "blind_box_deposit";
+/// 提交投票承诺
+pub const COMMIT: &str = "blind_box_commit";
+/// 揭示投票
+pub const REVEAL: &str = "blind_box_reveal";
+/// 结算时为地址分配档位
+pub const TIER_ASSIGNED: &str = "blind_box_tier_assigned";
+/// 结算返还
+pub const PAYOUT: &str = "blind_box_payout";
+/// 投票阶段变更
+pub const PHASE_CHANGE: &str = "blind_box_phase_change";
+
+/// 事件属性键
+pub mod keys {
+    pub const VERSION: &str = "version";
+    pub const SENDER: &str = "sender";
+    pub const AMOUNT: &str = "amount";
+    pub const COST: &str = "cost";
+    pub const FIRST_TOKEN_ID: &str = "first_token_id";
+    pub const MINTED: &str = "minted";
+    pub const VOTER: &str = "voter";
+    pub const COMMITMENT: &str = "commitment";
+    pub const ADDRESS: &str = "address";
+    pub const TIER: &str = "tier";
+    pub const RECIPIENT: &str = "recipient";
+    pub const FROM: &str = "from";
+    pub const TO: &str = "to";
+}
+
+/// 带版本属性的空事件
+fn versioned(ty: &str) -> Event {
+    Event::new(ty).add_attribute(keys::VERSION, EVENT_SCHEMA_VERSION)
+}
+
+/// 将投票阶段转为事件中使用的字符串
+fn phase_name(state: &VoteState) -> &'static str {
+    match state {
+        VoteState::Commit => "commit",
+        VoteState::Reveal => "reveal",
+        VoteState::Closed => "closed",
+    }
+}
+
+/// 多币种金额按 `100ujunox,20uatom` 格式拼接
+fn format_coins(coins: &[Coin]) -> String {
+    coins.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(",")
+}
+
+/// 充值事件：paid 为实收金额（不含退款），铸造 token_id 为 [first_token_id, first_token_id + minted)
+pub fn deposit_event(sender: &Addr, paid: &Coin, cost: Uint128, first_token_id: u64, minted: u64) -> Event {
+    versioned(DEPOSIT)
+        .add_attribute(keys::SENDER, sender.as_str())
+        .add_attribute(keys::AMOUNT, paid.to_string())
+        .add_attribute(keys::COST, cost)
+        .add_attribute(keys::FIRST_TOKEN_ID, first_token_id.to_string())
+        .add_attribute(keys::MINTED, minted.to_string())
+}
+
+/// 承诺事件
+pub fn commit_event(voter: &Addr, commitment: &str) -> Event {
+    versioned(COMMIT)
+        .add_attribute(keys::VOTER, voter.as_str())
+        .add_attribute(keys::COMMITMENT, commitment)
+}
+
+/// 揭示事件
+pub fn reveal_event(voter: &Addr) -> Event {
+    versioned(REVEAL).add_attribute(keys::VOTER, voter.as_str())
+}
+
+/// 档位分配事件
+pub fn tier_assigned_event(address: &Addr, tier: u8) -> Event {
+    versioned(TIER_ASSIGNED)
+        .add_attribute(keys::ADDRESS, address.as_str())
+        .add_attribute(keys::TIER, tier.to_string())
+}
+
+/// 结算返还事件
+pub fn payout_event(recipient: &str, amount: &[Coin]) -> Event {
+    versioned(PAYOUT)
+        .add_attribute(keys::RECIPIENT, recipient)
+        .add_attribute(keys::AMOUNT, format_coins(amount))
+}
+
+/// 阶段变更事件
+pub fn phase_change_event(from: &VoteState, to: &VoteState) -> Event {
+    versioned(PHASE_CHANGE)
+        .add_attribute(keys::FROM, phase_name(from))
+        .add_attribute(keys::TO, phase_name(to))
+}
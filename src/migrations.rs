@@ -0,0 +1,173 @@
+//! 存储迁移步骤
+//!
+//! 每个步骤对应一个存储结构版本（`STORAGE_VERSION`），`migrate` 按顺序执行所有高于
+//! 已存储版本的步骤。新增步骤只能追加到 `STEPS` 末尾，已发布的步骤不可修改。
+use cosmwasm_std::{Addr, Coin, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::{Item, Map};
+
+use crate::state::{load_config, save_tier, CommitInfo, RevealInfo, CONFIG, DEPOSITS, DEPOSIT_RANK, EXTRA_DEPOSITS, COMMITS, PAYOUTS, RESOLVED_PRINCIPAL, REVEALS, SETTLED, SETTLED_ROUND, SETTLEMENT_EPOCH, STORAGE_VERSION, TOTAL_PRINCIPAL, VOTER_COUNTS, VoterCounts};
+
+// 按轮次分区之前的单轮布局，仅供迁移步骤读取
+pub const LEGACY_COMMITS: Map<Addr, CommitInfo> = Map::new("commits");
+pub const LEGACY_REVEALS: Map<Addr, RevealInfo> = Map::new("reveals");
+pub const LEGACY_VOTER_COUNTS: Item<VoterCounts> = Item::new("voter_counts");
+pub const LEGACY_PAYOUTS: Map<&Addr, Vec<Coin>> = Map::new("payouts");
+pub const LEGACY_TIERS: Map<Addr, u8> = Map::new("tiers");
+pub const LEGACY_TIER_INDEX: Map<(u8, &Addr), ()> = Map::new("tier_index");
+pub const LEGACY_TIER_COUNTS: Map<u8, u64> = Map::new("tier_counts");
+
+/// 迁移步骤：名称与执行函数
+pub struct MigrationStep {
+    pub name: &'static str,
+    pub run: fn(&mut dyn Storage) -> StdResult<()>,
+}
+
+/// 按顺序排列的迁移步骤，第 i 个步骤将存储升级到版本 i + 1
+pub const STEPS: &[MigrationStep] = &[
+    MigrationStep { name: "backfill_total_principal", run: backfill_total_principal },
+    MigrationStep { name: "backfill_settled", run: backfill_settled },
+    MigrationStep { name: "rewrite_config", run: rewrite_config },
+    MigrationStep { name: "backfill_voter_counts", run: backfill_voter_counts },
+    MigrationStep { name: "backfill_tier_index", run: backfill_tier_index },
+    MigrationStep { name: "backfill_deposit_rank", run: backfill_deposit_rank },
+    MigrationStep { name: "backfill_settlement_epoch", run: backfill_settlement_epoch },
+    MigrationStep { name: "key_round_maps", run: key_round_maps },
+];
+
+/// 当前代码对应的存储结构版本
+pub fn latest_storage_version() -> u32 {
+    STEPS.len() as u32
+}
+
+/// 执行所有未应用的步骤，返回已执行步骤的名称
+pub fn run_pending(storage: &mut dyn Storage) -> StdResult<Vec<&'static str>> {
+    let current = STORAGE_VERSION.may_load(storage)?.unwrap_or(0);
+    let mut applied = vec![];
+    for (i, step) in STEPS.iter().enumerate().skip(current as usize) {
+        (step.run)(storage)?;
+        STORAGE_VERSION.save(storage, &(i as u32 + 1))?;
+        applied.push(step.name);
+    }
+    Ok(applied)
+}
+
+/// 由各地址本金汇总各币种的本金总额（TOTAL_PRINCIPAL 引入前的部署）
+fn backfill_total_principal(storage: &mut dyn Storage) -> StdResult<()> {
+    let cfg = load_config(storage)?;
+    let mut totals: Vec<(String, Uint128)> = vec![];
+    let mut add = |denom: String, amount: Uint128| {
+        match totals.iter_mut().find(|(d, _)| *d == denom) {
+            Some((_, total)) => *total += amount,
+            None => totals.push((denom, amount)),
+        }
+    };
+    for item in DEPOSITS.range(storage, None, None, Order::Ascending) {
+        let (_, payout) = item?;
+        add(cfg.base.denom.clone(), payout.principal);
+    }
+    for item in EXTRA_DEPOSITS.range(storage, None, None, Order::Ascending) {
+        let ((_, denom), amount) = item?;
+        add(denom, amount);
+    }
+    for (denom, total) in totals {
+        TOTAL_PRINCIPAL.save(storage, &denom, &total)?;
+    }
+    Ok(())
+}
+
+/// 将旧结构的配置按当前结构重新写入
+fn rewrite_config(storage: &mut dyn Storage) -> StdResult<()> {
+    let cfg = load_config(storage)?;
+    CONFIG.save(storage, &cfg)
+}
+
+/// 统计已有的承诺与揭示人数（VOTER_COUNTS 引入前的部署）
+fn backfill_voter_counts(storage: &mut dyn Storage) -> StdResult<()> {
+    let counts = VoterCounts {
+        commits: LEGACY_COMMITS.keys(storage, None, None, Order::Ascending).count() as u64,
+        reveals: LEGACY_REVEALS.keys(storage, None, None, Order::Ascending).count() as u64,
+    };
+    LEGACY_VOTER_COUNTS.save(storage, &counts)
+}
+
+/// 已写入分层结果的部署视为已结算（SETTLED 引入前的部署）
+fn backfill_settled(storage: &mut dyn Storage) -> StdResult<()> {
+    if LEGACY_TIERS.keys(storage, None, None, Order::Ascending).next().is_some() {
+        SETTLED.save(storage, &true)?;
+    }
+    Ok(())
+}
+
+/// 为已有分层结果建立 (档位, 地址) 索引与各档计数
+fn backfill_tier_index(storage: &mut dyn Storage) -> StdResult<()> {
+    let tiers = LEGACY_TIERS
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let mut counts = [0u64; 256];
+    for (addr, tier) in tiers {
+        LEGACY_TIER_INDEX.save(storage, (tier, &addr), &())?;
+        counts[tier as usize] += 1;
+    }
+    for (tier, count) in counts.iter().enumerate().filter(|(_, c)| **c > 0) {
+        LEGACY_TIER_COUNTS.save(storage, tier as u8, count)?;
+    }
+    Ok(())
+}
+
+/// 为已有充值建立本金排名索引
+fn backfill_deposit_rank(storage: &mut dyn Storage) -> StdResult<()> {
+    let deposits = DEPOSITS
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (addr, payout) in deposits {
+        DEPOSIT_RANK.save(storage, (payout.principal.u128(), &addr), &())?;
+    }
+    Ok(())
+}
+
+/// 已结算的部署：截至目前的本金均已结清，进入结算周期 1
+fn backfill_settlement_epoch(storage: &mut dyn Storage) -> StdResult<()> {
+    if !SETTLED.may_load(storage)?.unwrap_or(false) {
+        return Ok(());
+    }
+    let totals = TOTAL_PRINCIPAL
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (denom, total) in totals {
+        RESOLVED_PRINCIPAL.save(storage, &denom, &total)?;
+    }
+    SETTLEMENT_EPOCH.save(storage, &1)?;
+    SETTLED_ROUND.save(storage, &load_config(storage)?.round)
+}
+
+/// 将单轮布局的承诺、揭示、分层与返还记录移入当前轮次的分区，并删除旧记录
+fn key_round_maps(storage: &mut dyn Storage) -> StdResult<()> {
+    let round = load_config(storage)?.round;
+    let commits = LEGACY_COMMITS.range(storage, None, None, Order::Ascending).collect::<StdResult<Vec<_>>>()?;
+    for (addr, commit) in commits {
+        COMMITS.save(storage, (round, &addr), &commit)?;
+        LEGACY_COMMITS.remove(storage, addr);
+    }
+    let reveals = LEGACY_REVEALS.range(storage, None, None, Order::Ascending).collect::<StdResult<Vec<_>>>()?;
+    for (addr, reveal) in reveals {
+        REVEALS.save(storage, (round, &addr), &reveal)?;
+        LEGACY_REVEALS.remove(storage, addr);
+    }
+    let tiers = LEGACY_TIERS.range(storage, None, None, Order::Ascending).collect::<StdResult<Vec<_>>>()?;
+    for (addr, tier) in tiers {
+        save_tier(storage, round, &addr, tier)?;
+        LEGACY_TIERS.remove(storage, addr);
+    }
+    LEGACY_TIER_INDEX.clear(storage);
+    LEGACY_TIER_COUNTS.clear(storage);
+    let payouts = LEGACY_PAYOUTS.range(storage, None, None, Order::Ascending).collect::<StdResult<Vec<_>>>()?;
+    for (addr, amount) in payouts {
+        PAYOUTS.save(storage, (round, &addr), &amount)?;
+        LEGACY_PAYOUTS.remove(storage, &addr);
+    }
+    if let Some(counts) = LEGACY_VOTER_COUNTS.may_load(storage)? {
+        VOTER_COUNTS.save(storage, round, &counts)?;
+        LEGACY_VOTER_COUNTS.remove(storage);
+    }
+    Ok(())
+}
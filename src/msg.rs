@@ -1,26 +1,48 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Coin};
-use crate::state::{VoteState, Scale};
+use cosmwasm_std::{Binary, Coin, Uint128};
+use cw20::Cw20ReceiveMsg;
+use crate::state::{BulkDiscount, CommitmentScheme, HiddenItem, MintTemplate, PhaseWindow, PriceCurve, Pricing, PrizeInfo, ProtocolFee, RevealDelegate, SaleMode, VoteState, Scale};
 
-/// 实例化参数：用于部署时设置规模、基础币种与一等奖中奖人数
+/// 实例化参数：用于部署时设置规模、基础币种、一等奖中奖人数与可选的自定义总供应量
 #[cw_serde]
 pub struct InstantiateMsg {
     pub scale: Scale,
     pub base: Coin,
     pub first_prize_count: Option<u32>,  // 可选的一等奖中奖人数，如果不提供则使用规模默认值
+    pub total_supply: Option<u64>,       // 可选的自定义总供应量，如果不提供则使用规模预设值（此时 scale 仅作为标签）
 }
 
 /// 执行消息入口（Execute）：涵盖参数更新、充值、投票、结算以及 NFT 合约操作
 #[cw_serde]
 pub enum ExecuteMsg {
     SetBase { base: Coin },
+    SetSaleMode { mode: SaleMode },          // 设置 base 资产的销售模式
+    SetRevenueSplit { shares: Vec<RevenueShareMsg> },  // 设置收益分账
+    WithdrawProceeds { amount: Coin, recipient: Option<String> },  // 提取不欠用户的收益
+    SetProtocolFee { fee_bps: u16, collector: Option<String>, on_payouts: bool },  // 设置协议费
+    SetCommitmentScheme { scheme: CommitmentScheme },  // 首个承诺前切换承诺方案
+    SetBallot { options: Vec<String> },  // 设置选票选项（空列表取消，选项不可重复），本轮有人承诺后不可修改
+    SetRevealDelegate { delegate: Option<RevealDelegateMsg> },  // 投票者授权他人代为揭示，公钥方式也可签名代为承诺（None 撤销）
+    RevealFor { voter: String, reveal: String, salt: String, signature: Option<Binary> },  // 代投票者揭示，承诺仍按投票者地址校验
+    BatchCommit { entries: Vec<SignedCommit> },  // 按投票者签名批量承诺，逐条返回结果
+    BatchReveal { entries: Vec<SignedReveal> },  // 按委托授权批量揭示，逐条返回结果
+    SetPricing {                             // 设置 base 之外的币种价格、批量折扣与价格曲线
+        extra_prices: Vec<Coin>,
+        bulk_discounts: Vec<BulkDiscount>,
+        curve: PriceCurve,
+    },
     Deposit {},
+    Receive(Cw20ReceiveMsg),                 // CW20 支付入口（内嵌 Cw20HookMsg）
+    SetCw20Token { token: Option<String>, base: Option<Coin> },  // 发售前将 base 资产设为 CW20；None 恢复为原生币，须同时给出原生 base
     SetVoteState { state: VoteState },
+    SetTotalSupply { total_supply: u64 },  // 售罄前下调总供应量
+    CloseSale {},                          // 提前结束发售（总供应量收缩为已铸造数量）
     // admin controls
     SetPaused { paused: bool },
     SetCommitWindow { start_height: Option<u64>, end_height: Option<u64>, start_time: Option<u64>, end_time: Option<u64> },
     SetRevealWindow { start_height: Option<u64>, end_height: Option<u64>, start_time: Option<u64>, end_time: Option<u64> },
     SetClosedWindow { start_height: Option<u64>, end_height: Option<u64>, start_time: Option<u64>, end_time: Option<u64> },
+    SetSaleWindow { start_height: Option<u64>, end_height: Option<u64>, start_time: Option<u64>, end_time: Option<u64> },  // 设置发售窗口
     SetNftContract { nft_contract: String },  // 设置NFT合约地址
     SetNftCodeId { code_id: u64 },           // 设置NFT合约代码ID
     InstantiateNftContract {                 // 实例化NFT合约
@@ -28,6 +50,15 @@ pub enum ExecuteMsg {
         symbol: String,
         base_uri: Option<String>,
     },
+    SetMintTemplate { template: MintTemplate },  // 设置铸造元数据模板
+    SetTierPrize {                           // 设置某一档的奖品NFT（None 表示取消）
+        tier: u8,
+        kind: Option<luckee_nft::types::NftKind>,
+        series_id: Option<String>,
+    },
+    CommitDistribution { distribution_hash: String },  // 发售前承诺隐藏分布的哈希
+    RevealDistribution { items: Vec<HiddenItem>, salt: String },  // 公布分布，校验一次后按种子洗牌并保存各已售 token 的隐藏内容
+    PushRevealedMetadata { start_after: Option<u64>, limit: Option<u32> },  // 按 token_id 分页将已保存的隐藏内容推送到 NFT 元数据
     CommitVote { commitment: String },
     RevealVote { reveal: String, salt: String },
     Finalize {},
@@ -39,20 +70,69 @@ pub enum ExecuteMsg {
     RevokeAll { operator: String },
 }
 
+/// CW20 `Send` 内嵌的钩子消息
+#[cw_serde]
+pub enum Cw20HookMsg {
+    Deposit {},
+}
+
 /// 查询消息入口（Query）：查询配置、充值、本人的分层、NFT、授权等
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
     #[returns(ConfigResponse)]
     Config {},
+    #[returns(StatusResponse)]
+    Status {},
     #[returns(DepositResponse)]
     DepositOf { address: String },
+    #[returns(CurrentPriceResponse)]
+    CurrentPrice {},
+    #[returns(SaleStatusResponse)]
+    SaleStatus {},
+    #[returns(TreasuryResponse)]
+    Treasury {},
+    #[returns(QuoteResponse)]
+    Quote { denom: String, units: u64 },
+    #[returns(UserInfoResponse)]
+    UserInfo { address: String },
     #[returns(TierResponse)]
     TierOf { address: String },
+    #[returns(PrizesResponse)]
+    PrizesOf { address: String },
+    #[returns(RevealStatusResponse)]
+    RevealStatus {},
+    #[returns(RevealedItemResponse)]
+    RevealedItem { token_id: u64 },
+    #[returns(PreviewMintResponse)]
+    PreviewMint { token_id: u64 },
     #[returns(OwnerOfResponse)]
     OwnerOf { token_id: u64 },
+    /// 按当前方案与轮次计算承诺（reveal/salt 会发送给查询节点，敏感场景请用 `commitment` 模块本地计算）
+    #[returns(ComputeCommitmentResponse)]
+    ComputeCommitment { voter: String, reveal: String, salt: String },
+    #[returns(TierCountsResponse)]
+    TierCounts {},
     #[returns(TierListResponse)]
     TierList { tier: u8, start_after: Option<String>, limit: Option<u32> },
+    /// 按地址分页列出充值记录（含仅以其他币种充值的地址）
+    #[returns(AllDepositsResponse)]
+    AllDeposits { start_after: Option<String>, limit: Option<u32> },
+    /// 按地址分页列出承诺
+    #[returns(AllCommitsResponse)]
+    AllCommits { start_after: Option<String>, limit: Option<u32> },
+    /// 按地址分页列出揭示
+    #[returns(AllRevealsResponse)]
+    AllReveals { start_after: Option<String>, limit: Option<u32> },
+    /// base 本金最多的前 limit 个地址（只按 base 币种排名，仅以其他币种充值的地址不参与）
+    #[returns(TopDepositorsResponse)]
+    TopDepositors { limit: Option<u32> },
+    /// 投票者的委托揭示方式
+    #[returns(RevealDelegateResponse)]
+    RevealDelegate { voter: String },
+    /// 当前轮次各选项的票数
+    #[returns(VoteResultsResponse)]
+    VoteResults {},
     // CW721-like
     #[returns(NftInfoResponse)]
     NftInfo { token_id: u64 },
@@ -80,23 +160,142 @@ pub struct ConfigResponse {
     pub first_prize_count: u32,
     pub nft_contract: Option<String>,
     pub nft_code_id: Option<u64>,
+    pub mint_template: MintTemplate,
+    pub pricing: Pricing,
+    pub cw20_token: Option<String>,
+    pub sale_mode: SaleMode,
+    pub protocol_fee: ProtocolFee,
+    pub paused: bool,
+    pub next_token_id: u64,
+    pub commit_window: PhaseWindow,
+    pub reveal_window: PhaseWindow,
+    pub closed_window: PhaseWindow,
+    pub sale_window: PhaseWindow,
+    pub commitment_scheme: CommitmentScheme,
+    pub round: u64,
+}
+
+/// 承诺计算结果
+#[cw_serde]
+pub struct ComputeCommitmentResponse { pub scheme: CommitmentScheme, pub round: u64, pub commitment: String }
+
+/// 一次购买铸造的连续 token_id：[first_token_id, first_token_id + count)
+#[cw_serde]
+pub struct TokenRange { pub first_token_id: u64, pub count: u64 }
+
+/// 用户面板查询返回
+#[cw_serde]
+pub struct UserInfoResponse {
+    pub principal: Vec<Coin>,
+    pub units_purchased: u64,
+    pub purchases: Vec<TokenRange>,
+    pub committed: bool,
+    pub revealed: bool,
+    pub tier: Option<u8>,
+    /// 已分档时为按档位倍数（扣除结算协议费后）的返还金额
+    pub payout: Vec<Coin>,
+    /// 已结算且已分档，返还已随结算发出
+    pub paid_out: bool,
+    /// 有本金且已揭示、尚未结算：结算时可获得返还
+    pub refund_eligible: bool,
+}
+
+/// 阶段窗口及其在查询区块是否生效
+#[cw_serde]
+pub struct WindowStatus { pub window: PhaseWindow, pub active: bool }
+
+/// 运行状态查询返回：前端据此判断各操作当前能否成功
+#[cw_serde]
+pub struct StatusResponse {
+    pub paused: bool,
+    pub vote_state: VoteState,
+    pub minted: u64,
+    pub total_supply: u64,
+    pub remaining: u64,
+    pub commits: u64,
+    pub reveals: u64,
+    pub settled: bool,
+    pub commit_window: WindowStatus,
+    pub reveal_window: WindowStatus,
+    pub closed_window: WindowStatus,
+    pub sale_window: WindowStatus,
 }
 
-/// 迁移参数：空置接口，为未来升级预留
+/// 迁移参数：可选地在升级时调整配置，未提供的字段保持不变
 #[cw_serde]
+#[derive(Default)]
 pub struct MigrateMsg {
-    // 空置接口，当前不需要任何参数
-    // 为未来可能的合约升级预留
+    pub owner: Option<String>,
+    pub paused: Option<bool>,
+    pub first_prize_count: Option<u32>,
+    pub sale_window: Option<PhaseWindow>,
 }
 
-/// 充值查询返回：累计充值本金（字符串表示）
+/// luckee_nft `Minter {}` 查询的返回，SetNftContract 据此校验目标合约
 #[cw_serde]
-pub struct DepositResponse { pub principal: String }
+pub struct NftMinterResponse { pub minter: Option<String> }
+
+/// 充值查询返回：base 币种累计充值本金（字符串表示）及其他币种本金
+#[cw_serde]
+pub struct DepositResponse { pub principal: String, pub others: Vec<Coin> }
+
+/// 收益分账项：address 获得 share_bps 基点
+#[cw_serde]
+pub struct RevenueShareMsg { pub address: String, pub share_bps: u16 }
+
+/// 单个币种的金库情况：余额、应付用户、可提取与已提取
+#[cw_serde]
+pub struct TreasuryBalance {
+    pub denom: String,
+    pub balance: Uint128,
+    pub owed: Uint128,
+    pub withdrawable: Uint128,
+    pub withdrawn: Uint128,
+    pub fees_collected: Uint128,
+}
+
+/// 金库查询返回
+#[cw_serde]
+pub struct TreasuryResponse { pub settled: bool, pub balances: Vec<TreasuryBalance>, pub revenue_split: Vec<RevenueShareMsg> }
+
+/// 发售状态查询返回：是否可充值、已售/剩余数量及窗口结束点
+#[cw_serde]
+pub struct SaleStatusResponse {
+    pub open: bool,
+    pub sold: u64,
+    pub remaining: u64,
+    pub ends_at_height: Option<u64>,
+    pub ends_at_time: Option<u64>,
+}
+
+/// 当前价格查询返回：下一个 token 在当前区块的 base 单价与最近成交价
+#[cw_serde]
+pub struct CurrentPriceResponse { pub sale_mode: SaleMode, pub price: Coin, pub last_clearing_price: Option<Coin> }
+
+/// 报价查询返回：从当前 next_token_id 起购买 units 个的总价
+#[cw_serde]
+pub struct QuoteResponse { pub units: u64, pub cost: Coin }
 
 /// 分层查询返回：1/2/3（未设置为 0）
 #[cw_serde]
 pub struct TierResponse { pub tier: u8 }
 
+/// 奖品查询返回：结算时获得的奖品 NFT 列表
+#[cw_serde]
+pub struct PrizesResponse { pub prizes: Vec<PrizeInfo> }
+
+/// 揭晓状态查询返回：承诺哈希、随机种子（结算后固定）、分布是否已校验与元数据推送进度
+#[cw_serde]
+pub struct RevealStatusResponse { pub distribution_hash: Option<String>, pub seed: Option<String>, pub verified: bool, pub revealed: bool, pub revealed_count: u64 }
+
+/// 单个 token 的揭晓结果（未揭晓为 None）
+#[cw_serde]
+pub struct RevealedItemResponse { pub item: Option<HiddenItem> }
+
+/// 铸造预览返回：按当前模板为指定 token_id 生成的元数据
+#[cw_serde]
+pub struct PreviewMintResponse { pub token_id: u64, pub extension: luckee_nft::types::NftMeta }
+
 /// NFT 拥有者查询返回
 #[cw_serde]
 pub struct OwnerOfResponse { pub owner: String }
@@ -105,6 +304,80 @@ pub struct OwnerOfResponse { pub owner: String }
 #[cw_serde]
 pub struct TierListResponse { pub addresses: Vec<String>, pub next_start_after: Option<String> }
 
+/// 充值记录：base 本金及其他币种本金
+#[cw_serde]
+pub struct DepositEntry { pub address: String, pub principal: Uint128, pub others: Vec<Coin> }
+
+#[cw_serde]
+pub struct AllDepositsResponse { pub deposits: Vec<DepositEntry> }
+
+#[cw_serde]
+pub struct CommitEntry { pub address: String, pub commitment: String }
+
+#[cw_serde]
+pub struct AllCommitsResponse { pub commits: Vec<CommitEntry> }
+
+#[cw_serde]
+pub struct RevealEntry { pub address: String, pub reveal: String, pub salt: String }
+
+#[cw_serde]
+pub struct AllRevealsResponse { pub reveals: Vec<RevealEntry> }
+
+/// 按 base 本金从高到低排列；denom 为排名所依据的 base 币种
+#[cw_serde]
+pub struct TopDepositorsResponse { pub denom: String, pub depositors: Vec<DepositEntry> }
+
+/// 委托揭示方式（address 为 relayer 地址，pubkey 为签名公钥）
+#[cw_serde]
+pub enum RevealDelegateMsg {
+    Relayer { address: String },
+    Secp256k1 { pubkey: Binary },
+    Ed25519 { pubkey: Binary },
+}
+
+#[cw_serde]
+pub struct RevealDelegateResponse { pub delegate: Option<RevealDelegate> }
+
+/// 批量承诺条目（须附投票者签名，relayer 委托不能代为承诺）
+#[cw_serde]
+pub struct SignedCommit {
+    pub voter: String,
+    pub commitment: String,
+    pub signature: Option<Binary>,
+}
+
+/// 批量揭示条目（relayer 代发时 signature 可省略）
+#[cw_serde]
+pub struct SignedReveal {
+    pub voter: String,
+    pub reveal: String,
+    pub salt: String,
+    pub signature: Option<Binary>,
+}
+
+/// 批量条目的处理结果，失败时 error 为错误信息
+#[cw_serde]
+pub struct BatchEntryResult {
+    pub voter: String,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// `BatchCommit` / `BatchReveal` 响应 data，与输入条目一一对应
+#[cw_serde]
+pub struct BatchResponse { pub results: Vec<BatchEntryResult> }
+
+/// 单个选项的计票
+#[cw_serde]
+pub struct OptionTally { pub index: u32, pub label: String, pub votes: u64 }
+
+#[cw_serde]
+pub struct VoteResultsResponse { pub round: u64, pub options: Vec<OptionTally>, pub total_votes: u64 }
+
+/// 各档位地址数量
+#[cw_serde]
+pub struct TierCountsResponse { pub first: u64, pub second: u64, pub third: u64 }
+
 #[cw_serde]
 pub struct NftInfoResponse { pub owner: String, pub approved: Option<String> }
 
@@ -1,4 +1,4 @@
-use cosmwasm_std::{Addr, Coin, Uint128};
+use cosmwasm_std::{Addr, Binary, Coin, StdResult, Storage, Uint128};
 use cw_storage_plus::{Item, Map};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -19,6 +19,127 @@ pub struct Config {
     pub closed_window: PhaseWindow,
     pub nft_contract: Option<Addr>,  // NFT合约地址
     pub nft_code_id: Option<u64>,   // NFT合约代码ID，用于实例化
+    #[serde(default)]
+    pub mint_template: MintTemplate,  // 铸造元数据模板
+    #[serde(default)]
+    pub pricing: Pricing,             // 多币种价格表、批量折扣与价格曲线
+    #[serde(default)]
+    pub cw20_token: Option<Addr>,     // 设置后 base 资产为该 CW20（base.denom 即代币合约地址）
+    #[serde(default)]
+    pub sale_mode: SaleMode,          // base 资产的销售模式（固定价/荷兰拍/联合曲线）
+    #[serde(default)]
+    pub sale_window: PhaseWindow,     // 发售窗口，窗口外拒绝充值
+    #[serde(default)]
+    pub revenue_split: Vec<RevenueShare>, // 提取收益时的分账比例，剩余部分归提取接收者
+    #[serde(default)]
+    pub protocol_fee: ProtocolFee,    // 协议费（充值及可选的结算返还）
+    #[serde(default)]
+    pub commitment_scheme: CommitmentScheme, // 投票承诺方案（旧部署为 Legacy）
+    #[serde(default)]
+    pub round: u64,                   // 投票轮次，Closed 回到 Commit 时加一
+}
+
+/// 读取配置；0.1.0 部署的配置缺少其后新增的字段，按各字段的 serde 默认值补齐
+///
+/// 之后新增的 Config 字段一律带 `#[serde(default)]`；若某次改动无法用默认值兼容，
+/// 需保留旧结构并在此增加回退分支。
+pub fn load_config(storage: &dyn Storage) -> StdResult<Config> {
+    CONFIG.load(storage)
+}
+
+/// 收益分账：提取时按基点分给指定地址（如 DAO 金库、创作者、平台）
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RevenueShare {
+    pub address: Addr,
+    pub share_bps: u16,
+}
+
+/// 协议费：按基点从充值（及可选的结算返还）中扣除并发送给 collector
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct ProtocolFee {
+    pub fee_bps: u16,
+    pub collector: Option<Addr>,
+    pub on_payouts: bool,
+}
+
+/// 协议费上限（基点，10%）
+pub const MAX_PROTOCOL_FEE_BPS: u16 = 1_000;
+
+/// 投票承诺方案，见 `commitment` 模块
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub enum CommitmentScheme {
+    /// 旧格式 sha256(addr|reveal|salt)，未记录该字段的旧部署沿用
+    #[default]
+    Legacy,
+    /// 长度前缀编码，绑定链 ID、合约地址与轮次
+    V2,
+}
+
+/// 销售模式：决定 base 资产的单价
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub enum SaleMode {
+    /// 固定价格 base.amount
+    #[default]
+    Fixed,
+    /// 荷兰拍：在 [start_height, end_height] 内从 start_price 线性降至 floor_price
+    DutchAuction { start_price: Uint128, floor_price: Uint128, start_height: u64, end_height: u64 },
+    /// 联合曲线：第 token_id 个的单价为 base.amount + slope * token_id
+    BondingCurve { slope: Uint128 },
+}
+
+/// 定价配置：base 之外接受的币种及单价、批量折扣与按 next_token_id 的价格曲线
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct Pricing {
+    pub extra_prices: Vec<Coin>,
+    pub bulk_discounts: Vec<BulkDiscount>,
+    pub curve: PriceCurve,
+}
+
+/// 批量折扣：单笔购买不少于 min_units 个时按 discount_bps（基点）减价，取满足条件的最大折扣
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BulkDiscount {
+    pub min_units: u64,
+    pub discount_bps: u16,
+}
+
+/// 价格曲线：固定价格，或每售出 step_units 个单价上调 increase_bps（基点，按初始价计）
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub enum PriceCurve {
+    #[default]
+    Flat,
+    StepUp { step_units: u64, increase_bps: u16 },
+}
+
+/// 铸造元数据模板：决定 exec_deposit 中每个 token 的 NftMeta
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MintTemplate {
+    pub kind: luckee_nft::types::NftKind,
+    /// 系列命名模式，支持占位符 `{scale}`
+    pub series_pattern: String,
+    /// 每组 token 数量（0 表示不分组）
+    pub group_size: u64,
+    /// 可选的 token_id 区间 → 实物 SKU 映射（按顺序匹配第一条）
+    pub skus: Vec<SkuRule>,
+}
+
+/// token_id 闭区间 [start_id, end_id] 对应的实物 SKU
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SkuRule {
+    pub start_id: u64,
+    pub end_id: u64,
+    pub sku: String,
+}
+
+impl Default for MintTemplate {
+    /// 与早期硬编码行为一致：四叶草、blind_box_<scale>、每 1000 个一组、无 SKU
+    fn default() -> Self {
+        MintTemplate {
+            kind: luckee_nft::types::NftKind::Clover,
+            series_pattern: "blind_box_{scale}".to_string(),
+            group_size: 1000,
+            skus: vec![],
+        }
+    }
 }
 
 /// 投票状态机：提交/揭示/关闭
@@ -40,7 +161,7 @@ pub enum Scale {
 }
 
 /// 阶段窗口（可设置区块高度或时间的闭区间，满足已设置的所有维度）
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
 pub struct PhaseWindow {
     pub start_height: Option<u64>,
     pub end_height: Option<u64>,
@@ -69,16 +190,186 @@ pub struct Payout {
     pub principal: Uint128,
 }
 
+/// 分层奖品配置：结算时为该档中奖者铸造的 NFT 类型与系列
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TierPrize {
+    pub kind: luckee_nft::types::NftKind,
+    pub series_id: String,
+    pub minted: u64,  // 该档已铸造的奖品数量（用作系列内序号）
+}
+
+/// 已发放的奖品 NFT 记录
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PrizeInfo {
+    pub token_id: u64,
+    pub tier: u8,
+    pub kind: luckee_nft::types::NftKind,
+    pub series_id: String,
+}
+
+/// 隐藏内容：揭晓后分配给某个 token 的 NFT 类型与稀有度
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HiddenItem {
+    pub kind: luckee_nft::types::NftKind,
+    pub rarity: String,
+}
+
+/// 盲盒揭晓计划：发售前承诺的分布哈希、结算时固定的随机种子与是否已揭晓
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RevealPlan {
+    pub distribution_hash: String,
+    pub seed: Option<String>,
+    /// 分布已校验，各已售 token 的隐藏内容已写入 REVEALED_ITEMS
+    #[serde(default)]
+    pub verified: bool,
+    pub revealed: bool,
+    /// 已推送揭晓结果的 token 数量（token_id 0..revealed_count）
+    #[serde(default)]
+    pub revealed_count: u64,
+}
+
+/// 奖品 NFT 的 token_id 起点，与发售 token_id（0..total_supply）区分
+pub const PRIZE_TOKEN_ID_BASE: u64 = 1_000_000_000;
+
+/// 自定义总供应量的上限（与最大预设规模一致）
+pub const MAX_TOTAL_SUPPLY: u64 = 100_000;
+
 /// 单实例配置项
 pub const CONFIG: Item<Config> = Item::new("config");
 // 移除本地NFT存储，改为使用外部NFT合约
 // pub const TOKENS: Map<u64, TokenInfo> = Map::new("tokens");
 // pub const OPERATORS: Map<(Addr, Addr), bool> = Map::new("operators");
-pub const COMMITS: Map<Addr, CommitInfo> = Map::new("commits");
-pub const REVEALS: Map<Addr, RevealInfo> = Map::new("reveals");
+/// (轮次, 地址) → 承诺；按轮次分区，新一轮无需清理上一轮的记录
+pub const COMMITS: Map<(u64, &Addr), CommitInfo> = Map::new("round_commits");
+/// (轮次, 地址) → 揭示
+pub const REVEALS: Map<(u64, &Addr), RevealInfo> = Map::new("round_reveals");
 pub const DEPOSITS: Map<Addr, Payout> = Map::new("deposits");
-/// 地址 → 分层结果（1/2/3）
-pub const TIERS: Map<Addr, u8> = Map::new("tiers");
+/// 本金排名索引：(base 本金, 地址)，TopDepositors 倒序读取
+pub const DEPOSIT_RANK: Map<(u128, &Addr), ()> = Map::new("deposit_rank");
+/// (地址, 币种) → 以 base 之外币种充值的累计本金
+pub const EXTRA_DEPOSITS: Map<(&Addr, &str), Uint128> = Map::new("extra_deposits");
+/// 购买记录：(地址, 首个 token_id) → 本次铸造数量，token_id 连续
+pub const PURCHASES: Map<(&Addr, u64), u64> = Map::new("purchases");
+/// 投票人数计数（承诺/揭示各按地址计一次），避免查询时遍历
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct VoterCounts {
+    pub commits: u64,
+    pub reveals: u64,
+}
+
+/// 轮次 → 投票人数计数
+pub const VOTER_COUNTS: Map<u64, VoterCounts> = Map::new("round_voter_counts");
+/// 委托揭示方式：授权的 relayer 地址，或由用户公钥验证的离线签名（任何人可代为提交）
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub enum RevealDelegate {
+    Relayer { address: Addr },
+    Secp256k1 { pubkey: Binary },
+    Ed25519 { pubkey: Binary },
+}
+
+/// 投票地址 → 委托揭示方式
+pub const REVEAL_DELEGATES: Map<&Addr, RevealDelegate> = Map::new("reveal_delegates");
+/// 选票选项（为空时 reveal 为任意字符串，仅作随机熵）
+pub const BALLOT: Item<Vec<String>> = Item::new("ballot");
+/// 选票选项数量上限
+pub const MAX_BALLOT_OPTIONS: usize = 16;
+/// (轮次, 地址) → 该轮所选选项序号，每个地址每轮只计一票
+pub const BALLOT_VOTES: Map<(u64, &Addr), u32> = Map::new("ballot_votes");
+/// (轮次, 选项序号) → 票数
+pub const VOTE_TALLY: Map<(u64, u32), u64> = Map::new("vote_tally");
+/// 存储结构版本（已执行的迁移步骤数，见 `migrations::STEPS`）
+pub const STORAGE_VERSION: Item<u32> = Item::new("storage_version");
+/// 币种 → 用户累计本金总额（用于计算结算前的应付负债）
+pub const TOTAL_PRINCIPAL: Map<&str, Uint128> = Map::new("total_principal");
+/// 币种 → 拥有者累计提取的收益
+pub const WITHDRAWN: Map<&str, Uint128> = Map::new("withdrawn");
+/// 币种 → 累计收取的协议费
+pub const FEES_COLLECTED: Map<&str, Uint128> = Map::new("fees_collected");
+/// 当前轮次是否已完成结算（新一轮开始或结算后有新的充值时清除）
+pub const SETTLED: Item<bool> = Item::new("settled");
+/// 最近一次完成结算的轮次，同一轮次不可重复结算
+pub const SETTLED_ROUND: Item<u64> = Item::new("settled_round");
+/// 结算周期：每次完成结算加一，此前的本金视为已结清
+pub const SETTLEMENT_EPOCH: Item<u64> = Item::new("settlement_epoch");
+/// 地址在某结算周期开始时已结清的累计本金
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SettledPrincipal {
+    pub epoch: u64,
+    pub amount: Uint128,
+}
+
+/// (地址, 币种) → 已结清的累计本金；缺失表示该地址在结算周期 0 之后没有充值
+pub const SETTLED_PRINCIPAL: Map<(&Addr, &str), SettledPrincipal> = Map::new("settled_principal");
+/// 币种 → 已结清的本金总额，未结清部分 = TOTAL_PRINCIPAL - RESOLVED_PRINCIPAL
+pub const RESOLVED_PRINCIPAL: Map<&str, Uint128> = Map::new("resolved_principal");
+/// (轮次, 地址) → 该轮结算实际发放的返还（扣除结算协议费后）
+pub const PAYOUTS: Map<(u64, &Addr), Vec<Coin>> = Map::new("round_payouts");
+/// 最近一次成交的单价（base 资产，按最后一个售出 token 计）
+pub const LAST_CLEARING_PRICE: Item<Coin> = Item::new("last_clearing_price");
+/// (轮次, 地址) → 分层结果（1/2/3）
+pub const TIERS: Map<(u64, &Addr), u8> = Map::new("round_tiers");
+/// 分层二级索引：(轮次, 档位, 地址)，按档位分页时直接按范围读取
+pub const TIER_INDEX: Map<(u64, u8, &Addr), ()> = Map::new("round_tier_index");
+/// (轮次, 档位) → 地址数量
+pub const TIER_COUNTS: Map<(u64, u8), u64> = Map::new("round_tier_counts");
+
+/// 写入地址的 base 本金，同步维护本金排名索引
+pub fn save_deposit(storage: &mut dyn Storage, addr: &Addr, payout: &Payout) -> StdResult<()> {
+    if let Some(old) = DEPOSITS.may_load(storage, addr.clone())? {
+        DEPOSIT_RANK.remove(storage, (old.principal.u128(), addr));
+    }
+    DEPOSITS.save(storage, addr.clone(), payout)?;
+    DEPOSIT_RANK.save(storage, (payout.principal.u128(), addr), &())
+}
+
+/// 地址在某币种下尚未结清的本金；`total` 为该地址该币种的累计本金
+pub fn open_principal(storage: &dyn Storage, addr: &Addr, denom: &str, total: Uint128) -> StdResult<Uint128> {
+    let epoch = SETTLEMENT_EPOCH.may_load(storage)?.unwrap_or(0);
+    Ok(match SETTLED_PRINCIPAL.may_load(storage, (addr, denom))? {
+        Some(settled) if settled.epoch == epoch => total.saturating_sub(settled.amount),
+        // 基线早于当前周期：之后没有新的充值，累计本金均已结清
+        Some(_) => Uint128::zero(),
+        None if epoch == 0 => total,
+        None => Uint128::zero(),
+    })
+}
+
+/// 充值记账前调用：进入新的结算周期后首次充值时，记录此前已结清的累计本金
+pub fn rebase_principal(storage: &mut dyn Storage, addr: &Addr, denom: &str, total_before: Uint128) -> StdResult<()> {
+    let epoch = SETTLEMENT_EPOCH.may_load(storage)?.unwrap_or(0);
+    if epoch == 0 {
+        return Ok(());
+    }
+    match SETTLED_PRINCIPAL.may_load(storage, (addr, denom))? {
+        Some(settled) if settled.epoch == epoch => Ok(()),
+        _ => SETTLED_PRINCIPAL.save(storage, (addr, denom), &SettledPrincipal { epoch, amount: total_before }),
+    }
+}
+
+/// 写入地址在某轮次的档位，同步维护分层索引与计数（TIERS 只应通过此函数写入）
+pub fn save_tier(storage: &mut dyn Storage, round: u64, addr: &Addr, tier: u8) -> StdResult<()> {
+    if let Some(old) = TIERS.may_load(storage, (round, addr))? {
+        if old == tier {
+            return Ok(());
+        }
+        TIER_INDEX.remove(storage, (round, old, addr));
+        TIER_COUNTS.update(storage, (round, old), |c| -> StdResult<_> { Ok(c.unwrap_or_default().saturating_sub(1)) })?;
+    }
+    TIERS.save(storage, (round, addr), &tier)?;
+    TIER_INDEX.save(storage, (round, tier, addr), &())?;
+    TIER_COUNTS.update(storage, (round, tier), |c| -> StdResult<_> { Ok(c.unwrap_or_default() + 1) })?;
+    Ok(())
+}
+/// 分层（1/2/3）→ 奖品配置；未配置的档位不发放奖品 NFT
+pub const TIER_PRIZES: Map<u8, TierPrize> = Map::new("tier_prizes");
+/// 地址 → 已获得的奖品 NFT
+pub const PRIZES: Map<Addr, Vec<PrizeInfo>> = Map::new("prizes");
+/// 盲盒揭晓计划（未承诺时不存在）
+pub const REVEAL_PLAN: Item<RevealPlan> = Item::new("reveal_plan");
+/// token_id → 揭晓后的隐藏内容（分布校验时一次写入全部已售 token）
+pub const REVEALED_ITEMS: Map<u64, HiddenItem> = Map::new("revealed_items");
+/// 下一个奖品 NFT 的 token_id（未设置时从 PRIZE_TOKEN_ID_BASE 开始）
+pub const NEXT_PRIZE_TOKEN_ID: Item<u64> = Item::new("next_prize_token_id");
 
 impl Scale {
     /// 获取当前规模的总供应量
@@ -1,22 +1,29 @@
   #[cfg(test)]
 mod tests {
     // 单元测试：验证实例化与充值后按倍数铸造 NFT 的逻辑
-    use cosmwasm_std::{coins, testing::{mock_dependencies, mock_env}, MessageInfo};
-    use crate::{contract::{execute, instantiate}, msg::{ExecuteMsg, InstantiateMsg}, state::Scale};
+    use cosmwasm_std::{coins, testing::{mock_dependencies, mock_env}, to_json_binary, Addr, ContractInfoResponse, ContractResult, MessageInfo, SystemResult, WasmQuery};
+    use crate::{contract::{execute, instantiate}, msg::{ExecuteMsg, InstantiateMsg, NftMinterResponse}, state::Scale};
 
     #[test]
     fn instantiate_and_deposit_mints() {
         let mut deps = mock_dependencies();
         let env = mock_env();
-        let info = MessageInfo { sender: cosmwasm_std::Addr::unchecked("owner"), funds: vec![] };
-        instantiate(deps.as_mut(), env.clone(), info.clone(), InstantiateMsg { scale: Scale::Tiny, base: coins(100, "ujunox")[0].clone(), first_prize_count: None }).unwrap();
+        let info = MessageInfo { sender: deps.api.addr_make("owner"), funds: vec![] };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), InstantiateMsg { scale: Scale::Tiny, base: coins(100, "ujunox")[0].clone(), first_prize_count: None, total_supply: None }).unwrap();
 
-        // 设置NFT合约地址（使用unchecked地址避免验证问题）
-        execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::SetNftContract { 
-            nft_contract: cosmwasm_std::Addr::unchecked("nft_contract").to_string()
-        }).unwrap();
+        // 设置NFT合约地址（须为合法 bech32 地址），模拟的 NFT 合约以盲盒合约为铸造者
+        let nft_contract = deps.api.addr_make("nft_contract").to_string();
+        let minter = env.contract.address.to_string();
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::ContractInfo { .. } => {
+                let info = ContractInfoResponse::new(1, Addr::unchecked("creator"), None, false, None);
+                SystemResult::Ok(ContractResult::Ok(to_json_binary(&info).unwrap()))
+            }
+            _ => SystemResult::Ok(ContractResult::Ok(to_json_binary(&NftMinterResponse { minter: Some(minter.clone()) }).unwrap())),
+        });
+        execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::SetNftContract { nft_contract }).unwrap();
 
-        let info_user = MessageInfo { sender: cosmwasm_std::Addr::unchecked("user"), funds: coins(250, "ujunox") };
+        let info_user = MessageInfo { sender: deps.api.addr_make("user"), funds: coins(250, "ujunox") };
         let res = execute(deps.as_mut(), env, info_user, ExecuteMsg::Deposit {}).unwrap();
         assert_eq!(res.attributes.iter().find(|a| a.key == "minted").unwrap().value, "2");
     }
@@ -25,13 +32,14 @@ mod tests {
     fn test_nft_code_id_setting() {
         let mut deps = mock_dependencies();
         let env = mock_env();
-        let info = MessageInfo { sender: cosmwasm_std::Addr::unchecked("owner"), funds: vec![] };
+        let info = MessageInfo { sender: deps.api.addr_make("owner"), funds: vec![] };
         
         // 实例化盲盒合约
         instantiate(deps.as_mut(), env.clone(), info.clone(), InstantiateMsg { 
             scale: Scale::Tiny, 
             base: coins(100, "ujunox")[0].clone(), 
-            first_prize_count: None 
+            first_prize_count: None,
+            total_supply: None,
         }).unwrap();
 
         // 设置NFT合约代码ID
@@ -47,13 +55,14 @@ mod tests {
     fn test_nft_contract_instantiation_preparation() {
         let mut deps = mock_dependencies();
         let env = mock_env();
-        let info = MessageInfo { sender: cosmwasm_std::Addr::unchecked("owner"), funds: vec![] };
+        let info = MessageInfo { sender: deps.api.addr_make("owner"), funds: vec![] };
         
         // 实例化盲盒合约
         instantiate(deps.as_mut(), env.clone(), info.clone(), InstantiateMsg { 
             scale: Scale::Tiny, 
             base: coins(100, "ujunox")[0].clone(), 
-            first_prize_count: None 
+            first_prize_count: None,
+            total_supply: None,
         }).unwrap();
 
         // 设置NFT合约代码ID
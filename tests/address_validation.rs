@@ -0,0 +1,52 @@
+mod common;
+
+use cosmwasm_std::{coin, testing::MockApi};
+use dd_blind_box::{
+    contract::{execute, query},
+    msg::{ExecuteMsg, QueryMsg},
+    state::Scale,
+};
+use common::*;
+
+
+#[test]
+fn test_fixture_addresses_match_addr_make() {
+    let api = MockApi::default();
+    for (name, expected) in [("owner", OWNER), ("user1", USER1), ("user2", USER2), ("user3", USER3), ("operator", OPERATOR), ("nft_contract", NFT_CONTRACT)] {
+        assert_eq!(api.addr_make(name).as_str(), expected);
+    }
+}
+
+#[test]
+fn test_plain_strings_rejected() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    let owner = owner_info();
+
+    // 非 bech32 字符串与非规范（大写）地址均被拒绝
+    let upper = NFT_CONTRACT.to_uppercase();
+    let invalid = ["nft_contract", "cosmwasm1", upper.as_str()];
+    for address in invalid {
+        let msg = ExecuteMsg::SetNftContract { nft_contract: address.to_string() };
+        assert!(execute(deps.as_mut(), env.clone(), owner.clone(), msg).is_err());
+    }
+    let msg = ExecuteMsg::WithdrawProceeds { amount: coin(1, BASE_DENOM), recipient: Some("treasury".to_string()) };
+    assert!(execute(deps.as_mut(), env.clone(), owner, msg).is_err());
+
+    let result = query(deps.as_ref(), env, QueryMsg::AllDeposits { start_after: Some("user1".to_string()), limit: None });
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_nft_messages_forward_validated_address() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let (msg, info) = create_transfer_msg("user2".to_string(), 0);
+    assert!(execute(deps.as_mut(), env.clone(), info, msg).is_err());
+
+    let (msg, info) = create_transfer_msg(USER2.to_string(), 0);
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(res.attributes.iter().find(|a| a.key == "to").unwrap().value, USER2);
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use cosmwasm_std::Addr;
+use dd_blind_box::{
+    contract::{execute, query},
+    error::ContractError,
+    msg::{ExecuteMsg, QueryMsg, VoteResultsResponse},
+    state::{Scale, VoteState, REVEALS},
+};
+use common::*;
+
+
+fn set_ballot(deps: &mut MockDeps, env: &cosmwasm_std::Env, options: &[&str]) -> Result<cosmwasm_std::Response, ContractError> {
+    let options = options.iter().map(|o| o.to_string()).collect();
+    execute(deps.as_mut(), env.clone(), sender(OWNER), ExecuteMsg::SetBallot { options })
+}
+
+#[test]
+fn test_reveals_tallied_per_option() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    set_ballot(&mut deps, &env, &["keep", "burn", "airdrop"]).unwrap();
+
+    let votes = [(USER1, "1"), (USER2, "2"), (USER3, "5")];
+    for (user, choice) in votes {
+        let commitment = calculate_commitment(user, choice, "s");
+        execute(deps.as_mut(), env.clone(), sender(user), ExecuteMsg::CommitVote { commitment }).unwrap();
+    }
+    let (msg, info) = create_set_vote_state_msg(VoteState::Reveal);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    for (user, choice) in votes {
+        let msg = ExecuteMsg::RevealVote { reveal: choice.to_string(), salt: "s".to_string() };
+        let result = execute(deps.as_mut(), env.clone(), sender(user), msg);
+        if user == USER3 {
+            assert_eq!(result.unwrap_err(), ContractError::InvalidChoice { choice: "5".to_string(), options: 3 });
+        } else {
+            result.unwrap();
+        }
+    }
+    // 重复揭示不重复计票
+    let msg = ExecuteMsg::RevealVote { reveal: "1".to_string(), salt: "s".to_string() };
+    execute(deps.as_mut(), env.clone(), sender(USER1), msg).unwrap();
+
+    let res: VoteResultsResponse = cosmwasm_std::from_json(query(deps.as_ref(), env, QueryMsg::VoteResults {}).unwrap()).unwrap();
+    let votes: Vec<(String, u64)> = res.options.into_iter().map(|o| (o.label, o.votes)).collect();
+    assert_eq!(votes, vec![("keep".to_string(), 0), ("burn".to_string(), 1), ("airdrop".to_string(), 1)]);
+    assert_eq!(res.total_votes, 2);
+
+    // 选项揭示仍保存为结算随机熵
+    assert_eq!(REVEALS.load(&deps.storage, (0, &Addr::unchecked(USER2))).unwrap().reveal, "2");
+}
+
+#[test]
+fn test_ballot_validation_and_lock() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    assert!(set_ballot(&mut deps, &env, &["only"]).is_err());
+    assert!(set_ballot(&mut deps, &env, &["yes", " "]).is_err());
+    assert!(set_ballot(&mut deps, &env, &["yes", "no", "yes"]).is_err());
+    set_ballot(&mut deps, &env, &["yes", "no"]).unwrap();
+
+    // 本轮已有承诺后不可修改（承诺绑定的是选项序号）
+    let commitment = calculate_commitment(USER1, "0", "s");
+    execute(deps.as_mut(), env.clone(), sender(USER1), ExecuteMsg::CommitVote { commitment }).unwrap();
+    assert!(set_ballot(&mut deps, &env, &["no", "yes"]).is_err());
+
+    // 新一轮开始后可重新设置
+    for state in [VoteState::Reveal, VoteState::Closed, VoteState::Commit] {
+        let (msg, info) = create_set_vote_state_msg(state);
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    }
+    set_ballot(&mut deps, &env, &["a", "b", "c"]).unwrap();
+}
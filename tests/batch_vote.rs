@@ -0,0 +1,101 @@
+mod common;
+
+use cosmwasm_std::{testing::mock_env, Binary};
+use dd_blind_box::{
+    commitment::{commit_sign_digest, reveal_sign_digest},
+    contract::execute,
+    error::ContractError,
+    msg::{BatchResponse, ExecuteMsg, RevealDelegateMsg, SignedCommit, SignedReveal},
+    state::{Scale, VoteState},
+};
+use ed25519_zebra::{SigningKey, VerificationKey};
+use common::*;
+
+
+#[test]
+fn test_batch_commit_reports_each_entry() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    set_delegate(&mut deps, USER1, RevealDelegateMsg::Relayer { address: OPERATOR.to_string() });
+    let sk = SigningKey::from([9u8; 32]);
+    let pubkey: [u8; 32] = VerificationKey::from(&sk).into();
+    set_delegate(&mut deps, USER3, RevealDelegateMsg::Ed25519 { pubkey: Binary::from(pubkey) });
+
+    let commitment3 = calculate_commitment(USER3, "r", "s");
+    let digest = commit_sign_digest(&env.block.chain_id, env.contract.address.as_str(), 0, USER3, &commitment3);
+    let signed = SignedCommit { voter: USER3.to_string(), commitment: commitment3, signature: Some(Binary::from(sk.sign(&digest).to_bytes())) };
+    let entries = vec![
+        // relayer 委托只能代为揭示，不能代为承诺
+        SignedCommit { voter: USER1.to_string(), commitment: calculate_commitment(USER1, "r", "s"), signature: None },
+        // USER2 未授权
+        SignedCommit { voter: USER2.to_string(), commitment: calculate_commitment(USER2, "r", "s"), signature: None },
+        signed.clone(),
+    ];
+    let res = execute(deps.as_mut(), env.clone(), sender(OPERATOR), ExecuteMsg::BatchCommit { entries }).unwrap();
+
+    let results = cosmwasm_std::from_json::<BatchResponse>(res.data.unwrap()).unwrap().results;
+    assert_eq!(results.iter().map(|r| r.success).collect::<Vec<_>>(), vec![false, false, true]);
+    assert_eq!(results[0].error, Some(ContractError::Unauthorized.to_string()));
+    assert_eq!(results[1].voter, USER2);
+    assert_eq!(results[1].error, Some(ContractError::Unauthorized.to_string()));
+    assert_eq!(res.attributes.iter().find(|a| a.key == "failed").unwrap().value, "2");
+    assert_eq!(res.events.len(), 1);
+    assert_eq!(query_status(&deps).commits, 1);
+
+    // 投票者本人改投后，旧的签名承诺不能重放覆盖
+    let msg = ExecuteMsg::CommitVote { commitment: calculate_commitment(USER3, "x", "s") };
+    execute(deps.as_mut(), env.clone(), sender(USER3), msg).unwrap();
+    let res = execute(deps.as_mut(), env, sender(OPERATOR), ExecuteMsg::BatchCommit { entries: vec![signed] }).unwrap();
+    let results = cosmwasm_std::from_json::<BatchResponse>(res.data.unwrap()).unwrap().results;
+    assert_eq!(results[0].error, Some(ContractError::CommitmentExists.to_string()));
+}
+
+#[test]
+fn test_batch_reveal_isolates_failures() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    let sk = SigningKey::from([9u8; 32]);
+    let pubkey: [u8; 32] = VerificationKey::from(&sk).into();
+    for voter in [USER1, USER2] {
+        let msg = ExecuteMsg::CommitVote { commitment: calculate_commitment(voter, "r", "s") };
+        execute(deps.as_mut(), env.clone(), sender(voter), msg).unwrap();
+    }
+    set_delegate(&mut deps, USER1, RevealDelegateMsg::Relayer { address: OPERATOR.to_string() });
+    set_delegate(&mut deps, USER2, RevealDelegateMsg::Ed25519 { pubkey: Binary::from(pubkey) });
+
+    // 阶段不符时整批拒绝
+    let result = execute(deps.as_mut(), env.clone(), sender(OPERATOR), ExecuteMsg::BatchReveal { entries: vec![] });
+    assert_eq!(result.unwrap_err(), ContractError::RevealNotActive);
+    let (msg, info) = create_set_vote_state_msg(VoteState::Reveal);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let sign = |reveal: &str| {
+        let digest = reveal_sign_digest(&env.block.chain_id, env.contract.address.as_str(), 0, USER2, reveal, "s");
+        Some(Binary::from(sk.sign(&digest).to_bytes()))
+    };
+    let entries = vec![
+        // 揭示值与承诺不符
+        SignedReveal { voter: USER1.to_string(), reveal: "x".to_string(), salt: "s".to_string(), signature: None },
+        // 签名对应的揭示值不同
+        SignedReveal { voter: USER2.to_string(), reveal: "r".to_string(), salt: "s".to_string(), signature: sign("x") },
+        SignedReveal { voter: USER2.to_string(), reveal: "r".to_string(), salt: "s".to_string(), signature: sign("r") },
+        SignedReveal { voter: "not-an-address".to_string(), reveal: "r".to_string(), salt: "s".to_string(), signature: None },
+    ];
+    let res = execute(deps.as_mut(), env.clone(), sender(OPERATOR), ExecuteMsg::BatchReveal { entries }).unwrap();
+
+    let results = cosmwasm_std::from_json::<BatchResponse>(res.data.unwrap()).unwrap().results;
+    assert_eq!(results.iter().map(|r| r.success).collect::<Vec<_>>(), vec![false, false, true, false]);
+    assert!(results[0].error.as_ref().unwrap().contains("commitment mismatch"));
+    assert_eq!(results[1].error, Some(ContractError::InvalidSignature.to_string()));
+    assert_eq!(query_status(&deps).reveals, 1);
+
+    let result = execute(deps.as_mut(), env, sender(OPERATOR), ExecuteMsg::BatchReveal {
+        entries: vec![SignedReveal { voter: USER1.to_string(), reveal: "r".to_string(), salt: "s".to_string(), signature: None }; 101],
+    });
+    assert_eq!(result.unwrap_err(), ContractError::BatchTooLarge { count: 101, max: 100 });
+}
+
+fn set_delegate(deps: &mut MockDeps, voter: &str, delegate: RevealDelegateMsg) {
+    execute(deps.as_mut(), mock_env(), sender(voter), ExecuteMsg::SetRevealDelegate { delegate: Some(delegate) }).unwrap();
+}
+
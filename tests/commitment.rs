@@ -0,0 +1,93 @@
+mod common;
+
+use cosmwasm_std::testing::mock_env;
+use dd_blind_box::{
+    commitment::{commitment_legacy, commitment_v2},
+    contract::{execute, query},
+    msg::{ComputeCommitmentResponse, ExecuteMsg, QueryMsg},
+    state::{CommitmentScheme, Scale, VoteState},
+};
+use common::*;
+
+
+#[test]
+fn test_v2_fields_are_unambiguous_and_bound() {
+    let env = mock_env();
+    let chain = env.block.chain_id.as_str();
+    let contract = env.contract.address.as_str();
+
+    // 旧格式中 `|` 会造成歧义
+    assert_eq!(commitment_legacy(USER1, "a|b", "c"), commitment_legacy(USER1, "a", "b|c"));
+    assert_ne!(commitment_v2(chain, contract, 0, USER1, "a|b", "c"), commitment_v2(chain, contract, 0, USER1, "a", "b|c"));
+
+    let base = commitment_v2(chain, contract, 0, USER1, "r", "s");
+    assert_ne!(base, commitment_v2(chain, contract, 1, USER1, "r", "s"));
+    assert_ne!(base, commitment_v2(chain, OPERATOR, 0, USER1, "r", "s"));
+    assert_ne!(base, commitment_v2("other-chain", contract, 0, USER1, "r", "s"));
+}
+
+#[test]
+fn test_compute_commitment_query_matches_reveal() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    assert_eq!(query_config(&deps).commitment_scheme, CommitmentScheme::V2);
+
+    let computed = compute(&deps, USER1);
+    assert_eq!(computed.round, 0);
+    assert_eq!(computed.commitment, calculate_commitment(USER1, "r", "s"));
+
+    let (msg, info) = create_commit_msg(computed.commitment);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    let (msg, info) = create_set_vote_state_msg(VoteState::Reveal);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    let (msg, info) = create_reveal_msg("r".to_string(), "s".to_string());
+    execute(deps.as_mut(), env, info, msg).unwrap();
+}
+
+#[test]
+fn test_commitment_not_replayable_across_rounds() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    let round_zero = calculate_commitment(USER1, "r", "s");
+
+    for state in [VoteState::Reveal, VoteState::Closed, VoteState::Commit] {
+        let (msg, info) = create_set_vote_state_msg(state);
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    }
+    assert_eq!(query_config(&deps).round, 1);
+    assert_ne!(compute(&deps, USER1).commitment, round_zero);
+
+    // 沿用上一轮的承诺无法揭示
+    let (msg, info) = create_commit_msg(round_zero);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    let (msg, info) = create_set_vote_state_msg(VoteState::Reveal);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    let (msg, info) = create_reveal_msg("r".to_string(), "s".to_string());
+    assert!(execute(deps.as_mut(), env, info, msg).is_err());
+}
+
+#[test]
+fn test_legacy_scheme_behind_flag() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    let owner = owner_info();
+
+    let msg = ExecuteMsg::SetCommitmentScheme { scheme: CommitmentScheme::Legacy };
+    execute(deps.as_mut(), env.clone(), owner.clone(), msg).unwrap();
+    let (msg, info) = create_commit_msg(commitment_legacy(USER1, "r", "s"));
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // 已有承诺后不可切换
+    let msg = ExecuteMsg::SetCommitmentScheme { scheme: CommitmentScheme::V2 };
+    assert!(execute(deps.as_mut(), env.clone(), owner, msg).is_err());
+
+    let (msg, info) = create_set_vote_state_msg(VoteState::Reveal);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    let (msg, info) = create_reveal_msg("r".to_string(), "s".to_string());
+    execute(deps.as_mut(), env, info, msg).unwrap();
+}
+
+fn compute(deps: &MockDeps, voter: &str) -> ComputeCommitmentResponse {
+    let msg = QueryMsg::ComputeCommitment { voter: voter.to_string(), reveal: "r".to_string(), salt: "s".to_string() };
+    cosmwasm_std::from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap()
+}
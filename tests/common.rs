@@ -1,33 +1,63 @@
 #![allow(dead_code)]
 
 use cosmwasm_std::{
-    coins, testing::{mock_dependencies, mock_env}, Coin, Uint128, OwnedDeps, MessageInfo
+    coins, testing::{mock_dependencies, mock_env}, to_json_binary, Addr, Coin, ContractInfoResponse, ContractResult, Uint128, OwnedDeps, MessageInfo, SystemError, SystemResult, WasmQuery
 };
 use dd_blind_box::{
     contract::{instantiate, query, execute},
-    msg::{ExecuteMsg, InstantiateMsg, QueryMsg},
+    msg::{ExecuteMsg, InstantiateMsg, NftMinterResponse, QueryMsg},
     state::{Scale, VoteState},
 };
 
 /// 测试常量
-pub const OWNER: &str = "owner";
-pub const USER1: &str = "user1";
-pub const USER2: &str = "user2";
-pub const USER3: &str = "user3";
-pub const OPERATOR: &str = "operator";
+///
+/// 地址均为 `MockApi::default().addr_make(name)` 生成的 bech32 地址（name 见注释），
+/// 合约对传入地址做严格校验，普通字符串不再被接受
+pub const OWNER: &str = "cosmwasm1fsgzj6t7udv8zhf6zj32mkqhcjcpv52yph5qsdcl0qt94jgdckqs2g053y"; // owner
+pub const USER1: &str = "cosmwasm1pgzph9rze2j2xxavx4n7pdhxlkgsq7rak245x0vk7mgh3j4le6gqmlwcfu"; // user1
+pub const USER2: &str = "cosmwasm1vqjarrly327529599rcc4qhzvhwe34pp5uyy4gylvxe5zupeqx3sg08lap"; // user2
+pub const USER3: &str = "cosmwasm1tps04uptd0rzy2a94jjjx4s0pcmyenvtv7lwfph730muq82f9n9s2w0guk"; // user3
+pub const OPERATOR: &str = "cosmwasm1qmj4kce5s8mmkpef2l4teugse9ewse53c087m2lq3qpyhlly9u3s0hztgj"; // operator
+pub const NFT_CONTRACT: &str = "cosmwasm1pv9g4gkq46vjp58lwnd3pdwdrgvx0mmn46znu00ga9uue4z4nqgqle0d2c"; // nft_contract
+pub const NFT_CODE_ID: u64 = 1;
 pub const BASE_DENOM: &str = "ujunox";
 pub const BASE_AMOUNT: u128 = 100;
 
+/// 测试用的 mock 依赖
+pub type MockDeps = OwnedDeps<cosmwasm_std::MemoryStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier>;
+
 /// 创建测试环境
-pub fn setup_test_env() -> (OwnedDeps<cosmwasm_std::MemoryStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier>, cosmwasm_std::Env) {
+pub fn setup_test_env() -> (MockDeps, cosmwasm_std::Env) {
     let deps = mock_dependencies();
     let env = mock_env();
     (deps, env)
 }
 
+/// 模拟 luckee_nft 合约：NFT_CONTRACT 的代码 ID 为 NFT_CODE_ID，铸造者为 minter
+pub fn mock_nft_contract(
+    deps: &mut MockDeps,
+    minter: &str,
+) {
+    let minter = minter.to_string();
+    deps.querier.update_wasm(move |query| match query {
+        WasmQuery::ContractInfo { contract_addr } if contract_addr == NFT_CONTRACT => {
+            let info = ContractInfoResponse::new(NFT_CODE_ID, Addr::unchecked(OWNER), None, false, None);
+            SystemResult::Ok(ContractResult::Ok(to_json_binary(&info).unwrap()))
+        }
+        WasmQuery::Smart { contract_addr, .. } if contract_addr == NFT_CONTRACT => {
+            let res = NftMinterResponse { minter: Some(minter.clone()) };
+            SystemResult::Ok(ContractResult::Ok(to_json_binary(&res).unwrap()))
+        }
+        WasmQuery::ContractInfo { contract_addr } | WasmQuery::Smart { contract_addr, .. } => {
+            SystemResult::Err(SystemError::NoSuchContract { addr: contract_addr.clone() })
+        }
+        _ => SystemResult::Err(SystemError::UnsupportedRequest { kind: "wasm".to_string() }),
+    });
+}
+
 /// 初始化合约
 pub fn instantiate_contract(
-    deps: &mut OwnedDeps<cosmwasm_std::MemoryStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier>,
+    deps: &mut MockDeps,
     env: &cosmwasm_std::Env,
     scale: Scale,
     base_amount: u128,
@@ -43,12 +73,14 @@ pub fn instantiate_contract(
             amount: Uint128::from(base_amount),
         },
         first_prize_count: None,  // 使用规模默认值
+        total_supply: None,       // 使用规模默认供应量
     };
     let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg)?;
     
-    // 设置NFT合约地址（测试环境需要）
+    // 设置NFT合约地址（测试环境需要），模拟的 NFT 合约以盲盒合约为铸造者
+    mock_nft_contract(deps, env.contract.address.as_str());
     let set_nft_msg = ExecuteMsg::SetNftContract { 
-        nft_contract: cosmwasm_std::Addr::unchecked("nft_contract").to_string() 
+        nft_contract: NFT_CONTRACT.to_string() 
     };
     execute(deps.as_mut(), env.clone(), info, set_nft_msg)?;
     
@@ -125,12 +157,10 @@ pub fn create_finalize_msg() -> (ExecuteMsg, MessageInfo) {
     (msg, info)
 }
 
-/// 计算承诺哈希
+/// 计算承诺哈希（V2 方案，mock_env 的链 ID 与合约地址，第 0 轮）
 pub fn calculate_commitment(addr: &str, reveal: &str, salt: &str) -> String {
-    use sha2::{Digest, Sha256};
-    let preimage = format!("{}|{}|{}", addr, reveal, salt);
-    let hash = Sha256::digest(preimage.as_bytes());
-    hex::encode(hash)
+    let env = mock_env();
+    dd_blind_box::commitment::commitment_v2(&env.block.chain_id, env.contract.address.as_str(), 0, addr, reveal, salt)
 }
 
 /// 创建设置投票状态消息
@@ -163,51 +193,76 @@ pub fn create_set_base_msg(base: Coin) -> (ExecuteMsg, MessageInfo) {
     (msg, info)
 }
 
+/// 拥有者发送、不附带资金的消息信息
+pub fn owner_info() -> MessageInfo {
+    sender(OWNER)
+}
+
+/// 指定地址发送、不附带资金的消息信息
+pub fn sender(address: &str) -> MessageInfo {
+    MessageInfo { sender: Addr::unchecked(address), funds: vec![] }
+}
+
+/// 指定地址发送并附带 amount 个 BASE_DENOM 的消息信息
+pub fn funded(address: &str, amount: u128) -> MessageInfo {
+    MessageInfo { sender: Addr::unchecked(address), funds: coins(amount, BASE_DENOM) }
+}
 
 /// 查询配置
-pub fn query_config(deps: &OwnedDeps<cosmwasm_std::MemoryStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier>) -> dd_blind_box::msg::ConfigResponse {
+pub fn query_config(deps: &MockDeps) -> dd_blind_box::msg::ConfigResponse {
     let msg = QueryMsg::Config {};
     let res = query(deps.as_ref(), mock_env(), msg).unwrap();
     cosmwasm_std::from_json(res).unwrap()
 }
 
+/// 查询运行状态
+pub fn query_status(deps: &MockDeps) -> dd_blind_box::msg::StatusResponse {
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Status {}).unwrap();
+    cosmwasm_std::from_json(res).unwrap()
+}
+
 /// 查询充值
-pub fn query_deposit(deps: &OwnedDeps<cosmwasm_std::MemoryStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier>, address: &str) -> dd_blind_box::msg::DepositResponse {
+pub fn query_deposit(deps: &MockDeps, address: &str) -> dd_blind_box::msg::DepositResponse {
     let msg = QueryMsg::DepositOf { address: address.to_string() };
     let res = query(deps.as_ref(), mock_env(), msg).unwrap();
     cosmwasm_std::from_json(res).unwrap()
 }
 
 /// 查询分层
-pub fn query_tier(deps: &OwnedDeps<cosmwasm_std::MemoryStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier>, address: &str) -> dd_blind_box::msg::TierResponse {
+pub fn query_tier(deps: &MockDeps, address: &str) -> dd_blind_box::msg::TierResponse {
     let msg = QueryMsg::TierOf { address: address.to_string() };
     let res = query(deps.as_ref(), mock_env(), msg).unwrap();
     cosmwasm_std::from_json(res).unwrap()
 }
 
 /// 测试专用的查询充值函数，绕过地址验证
-pub fn query_deposit_test(deps: &OwnedDeps<cosmwasm_std::MemoryStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier>, address: &str) -> dd_blind_box::msg::DepositResponse {
+pub fn query_deposit_test(deps: &MockDeps, address: &str) -> dd_blind_box::msg::DepositResponse {
     use dd_blind_box::state::DEPOSITS;
     use cosmwasm_std::Addr;
     use cosmwasm_std::Uint128;
     
     let addr = Addr::unchecked(address);
-    let p = DEPOSITS.may_load(&deps.storage, addr).unwrap().unwrap_or(dd_blind_box::state::Payout { principal: Uint128::zero() });
-    dd_blind_box::msg::DepositResponse { principal: p.principal.to_string() }
+    let p = DEPOSITS.may_load(&deps.storage, addr.clone()).unwrap().unwrap_or(dd_blind_box::state::Payout { principal: Uint128::zero() });
+    let others = dd_blind_box::state::EXTRA_DEPOSITS.prefix(&addr)
+        .range(&deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .map(|item| item.map(|(denom, amount)| Coin { denom, amount }).unwrap())
+        .collect();
+    dd_blind_box::msg::DepositResponse { principal: p.principal.to_string(), others }
 }
 
 /// 测试专用的查询分层函数，绕过地址验证
-pub fn query_tier_test(deps: &OwnedDeps<cosmwasm_std::MemoryStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier>, address: &str) -> dd_blind_box::msg::TierResponse {
-    use dd_blind_box::state::TIERS;
+pub fn query_tier_test(deps: &MockDeps, address: &str) -> dd_blind_box::msg::TierResponse {
+    use dd_blind_box::state::{CONFIG, TIERS};
     use cosmwasm_std::Addr;
     
     let addr = Addr::unchecked(address);
-    let t = TIERS.may_load(&deps.storage, addr).unwrap().unwrap_or(0);
+    let round = CONFIG.load(&deps.storage).unwrap().round;
+    let t = TIERS.may_load(&deps.storage, (round, &addr)).unwrap().unwrap_or(0);
     dd_blind_box::msg::TierResponse { tier: t }
 }
 
 /// 查询NFT所有者
-pub fn query_owner_of(deps: &OwnedDeps<cosmwasm_std::MemoryStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier>, token_id: u64) -> dd_blind_box::msg::OwnerOfResponse {
+pub fn query_owner_of(deps: &MockDeps, token_id: u64) -> dd_blind_box::msg::OwnerOfResponse {
     let msg = QueryMsg::OwnerOf { token_id };
     let res = query(deps.as_ref(), mock_env(), msg).unwrap();
     cosmwasm_std::from_json(res).unwrap()
@@ -215,7 +270,7 @@ pub fn query_owner_of(deps: &OwnedDeps<cosmwasm_std::MemoryStorage, cosmwasm_std
 
 /// 查询分层列表
 pub fn query_tier_list(
-    deps: &OwnedDeps<cosmwasm_std::MemoryStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier>,
+    deps: &MockDeps,
     tier: u8,
     start_after: Option<String>,
     limit: Option<u32>,
@@ -226,14 +281,14 @@ pub fn query_tier_list(
 }
 
 /// 查询NFT信息
-pub fn query_nft_info(deps: &OwnedDeps<cosmwasm_std::MemoryStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier>, token_id: u64) -> dd_blind_box::msg::NftInfoResponse {
+pub fn query_nft_info(deps: &MockDeps, token_id: u64) -> dd_blind_box::msg::NftInfoResponse {
     let msg = QueryMsg::NftInfo { token_id };
     let res = query(deps.as_ref(), mock_env(), msg).unwrap();
     cosmwasm_std::from_json(res).unwrap()
 }
 
 /// 查询授权
-pub fn query_approval(deps: &OwnedDeps<cosmwasm_std::MemoryStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier>, token_id: u64) -> dd_blind_box::msg::ApprovalResponse {
+pub fn query_approval(deps: &MockDeps, token_id: u64) -> dd_blind_box::msg::ApprovalResponse {
     let msg = QueryMsg::Approval { token_id };
     let res = query(deps.as_ref(), mock_env(), msg).unwrap();
     cosmwasm_std::from_json(res).unwrap()
@@ -241,7 +296,7 @@ pub fn query_approval(deps: &OwnedDeps<cosmwasm_std::MemoryStorage, cosmwasm_std
 
 /// 查询全局授权
 pub fn query_is_approved_for_all(
-    deps: &OwnedDeps<cosmwasm_std::MemoryStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier>,
+    deps: &MockDeps,
     owner: &str,
     operator: &str,
 ) -> dd_blind_box::msg::IsApprovedForAllResponse {
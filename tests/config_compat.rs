@@ -0,0 +1,64 @@
+mod common;
+
+use cosmwasm_std::{testing::mock_env, Addr, Storage, Uint128};
+use cw2::set_contract_version;
+use dd_blind_box::{
+    contract::{execute, migrate},
+    msg::MigrateMsg,
+    state::{load_config, CommitmentScheme, Config, PriceCurve, ProtocolFee, SaleMode, Scale, VoteState},
+};
+use common::*;
+
+
+const CONFIG_V1: &str = include_str!("fixtures/config_v1.json");
+const CONFIG_V2_PRICING: &str = include_str!("fixtures/config_v2_pricing.json");
+
+#[test]
+fn test_load_config_v1_fixture() {
+    let (mut deps, _env) = setup_test_env();
+    deps.storage.set(b"config", CONFIG_V1.as_bytes());
+
+    let cfg = load_config(&deps.storage).unwrap();
+    assert_eq!(cfg.owner, Addr::unchecked(OWNER));
+    assert_eq!(cfg.vote_state, VoteState::Reveal);
+    assert_eq!(cfg.next_token_id, 3);
+    assert_eq!(cfg.reveal_window.start_height, Some(100));
+    assert_eq!(cfg.nft_code_id, Some(7));
+    // 0.1.0 之后新增的字段按 serde 默认值补齐
+    assert_eq!(cfg.sale_mode, SaleMode::Fixed);
+    assert_eq!(cfg.protocol_fee, ProtocolFee::default());
+    assert!(cfg.revenue_split.is_empty());
+    assert_eq!(cfg.commitment_scheme, CommitmentScheme::Legacy);
+}
+
+#[test]
+fn test_load_config_v2_fixture() {
+    let (mut deps, _env) = setup_test_env();
+    deps.storage.set(b"config", CONFIG_V2_PRICING.as_bytes());
+
+    let cfg = load_config(&deps.storage).unwrap();
+    assert_eq!(cfg.scale, Scale::Medium);
+    assert_eq!(cfg.total_supply, 500);
+    assert!(cfg.paused);
+    assert_eq!(cfg.pricing.extra_prices[0].amount, Uint128::from(5u128));
+    assert_eq!(cfg.pricing.curve, PriceCurve::Flat);
+    assert_eq!(cfg.cw20_token, None);
+}
+
+#[test]
+fn test_legacy_deployment_keeps_working_after_migrate() {
+    let (mut deps, env) = setup_test_env();
+    deps.storage.set(b"config", CONFIG_V1.as_bytes());
+    set_contract_version(&mut deps.storage, "crates.io:dd_blind_box", "0.1.0").unwrap();
+
+    let res = migrate(deps.as_mut(), env, MigrateMsg::default()).unwrap();
+    assert!(res.attributes.iter().any(|a| a.key == "steps" && a.value.contains("rewrite_config")));
+
+    // 迁移后配置按当前结构存储，可正常查询与执行
+    let stored: Config = cosmwasm_std::from_json(deps.storage.get(b"config").unwrap()).unwrap();
+    assert_eq!(stored.next_token_id, 3);
+    assert_eq!(query_config(&deps).vote_state, VoteState::Reveal);
+
+    let (msg, info) = create_set_vote_state_msg(VoteState::Commit);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+}
@@ -0,0 +1,146 @@
+mod common;
+
+use cosmwasm_std::{to_json_binary, Addr, Coin, CosmosMsg, MessageInfo, Uint128, WasmMsg};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use dd_blind_box::{
+    contract::execute,
+    error::ContractError,
+    msg::{Cw20HookMsg, ExecuteMsg},
+    state::{Scale, VoteState, CONFIG, REVEALS, RevealInfo},
+};
+use common::*;
+
+
+const TOKEN: &str = "cosmwasm1sxek6wltat3sl460jsqysg4wwgdwylz9kzjagug4qhypp4ux0cxsrjtd9g"; // addr_make("cw20_token")
+const OTHER_TOKEN: &str = "cosmwasm1rp04r5ehl2l6hycyjlfwlqlhuvag46kttrd287qcarklwlqd53qqvvesxj"; // addr_make("other_token")
+
+#[test]
+fn test_set_cw20_token() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let res = execute(deps.as_mut(), env, owner_info(), use_token()).unwrap();
+    assert_eq!(res.attributes[1].value, TOKEN);
+
+    let config = query_config(&deps);
+    assert_eq!(config.cw20_token, Some(TOKEN.to_string()));
+    assert_eq!(config.base.denom, TOKEN);
+    assert_eq!(config.base.amount, Uint128::from(BASE_AMOUNT));
+}
+
+#[test]
+fn test_clear_cw20_token_requires_native_base() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    execute(deps.as_mut(), env.clone(), owner_info(), use_token()).unwrap();
+
+    // 仅清除代币会让 base.denom 继续指向代币地址，被拒绝
+    let result = execute(deps.as_mut(), env.clone(), owner_info(), ExecuteMsg::SetCw20Token { token: None, base: None });
+    assert!(result.unwrap_err().to_string().contains("requires a native base"));
+    let base = Coin { denom: TOKEN.to_string(), amount: Uint128::from(BASE_AMOUNT) };
+    let result = execute(deps.as_mut(), env.clone(), owner_info(), ExecuteMsg::SetCw20Token { token: None, base: Some(base) });
+    assert!(result.is_err());
+
+    let base = Coin { denom: BASE_DENOM.to_string(), amount: Uint128::from(BASE_AMOUNT * 2) };
+    execute(deps.as_mut(), env.clone(), owner_info(), ExecuteMsg::SetCw20Token { token: None, base: Some(base.clone()) }).unwrap();
+    let config = query_config(&deps);
+    assert_eq!(config.cw20_token, None);
+    assert_eq!(config.base, base);
+
+    // 原生充值恢复可用
+    let res = execute(deps.as_mut(), env, funded(USER1, BASE_AMOUNT * 2), ExecuteMsg::Deposit {}).unwrap();
+    assert_eq!(res.attributes.iter().find(|a| a.key == "minted").unwrap().value, "1");
+}
+
+#[test]
+fn test_set_cw20_token_after_sale_started() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let (msg, info) = create_deposit_msg(BASE_AMOUNT);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let result = execute(deps.as_mut(), env, owner_info(), use_token());
+    assert_eq!(result.unwrap_err(), ContractError::InvalidState);
+}
+
+#[test]
+fn test_receive_deposit_mints() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    execute(deps.as_mut(), env.clone(), owner_info(), use_token()).unwrap();
+
+    let res = execute(deps.as_mut(), env, token_info(), receive_msg(USER1, BASE_AMOUNT * 2)).unwrap();
+    assert_eq!(res.attributes.iter().find(|a| a.key == "minted").unwrap().value, "2");
+    assert_eq!(res.attributes.iter().find(|a| a.key == "from").unwrap().value, USER1);
+    assert_eq!(query_deposit_test(&deps, USER1).principal, (BASE_AMOUNT * 2).to_string());
+    assert_eq!(CONFIG.load(&deps.storage).unwrap().next_token_id, 2);
+}
+
+#[test]
+fn test_receive_from_unknown_token() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    execute(deps.as_mut(), env.clone(), owner_info(), use_token()).unwrap();
+
+    let info = sender(OTHER_TOKEN);
+    let result = execute(deps.as_mut(), env, info, receive_msg(USER1, BASE_AMOUNT));
+    assert_eq!(result.unwrap_err(), ContractError::Unauthorized);
+}
+
+#[test]
+fn test_receive_without_cw20_configured() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let result = execute(deps.as_mut(), env, token_info(), receive_msg(USER1, BASE_AMOUNT));
+    assert_eq!(result.unwrap_err(), ContractError::Unauthorized);
+}
+
+#[test]
+fn test_native_deposit_rejected_for_cw20_base() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    execute(deps.as_mut(), env.clone(), owner_info(), use_token()).unwrap();
+
+    assert!(execute(deps.as_mut(), env, funded(USER1, BASE_AMOUNT), ExecuteMsg::Deposit {}).is_err());
+}
+
+#[test]
+fn test_finalize_pays_out_cw20() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    execute(deps.as_mut(), env.clone(), owner_info(), use_token()).unwrap();
+    execute(deps.as_mut(), env.clone(), token_info(), receive_msg(USER1, BASE_AMOUNT)).unwrap();
+
+    REVEALS.save(&mut deps.storage, (0, &Addr::unchecked(USER1)), &RevealInfo { reveal: "r".to_string(), salt: "s".to_string() }).unwrap();
+    let mut config = CONFIG.load(&deps.storage).unwrap();
+    config.vote_state = VoteState::Closed;
+    CONFIG.save(&mut deps.storage, &config).unwrap();
+
+    let (msg, info) = create_finalize_msg();
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    // 唯一投票者获得一等奖 2 倍返还，以 CW20 Transfer 发送
+    assert_eq!(res.messages[0].msg, CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: TOKEN.to_string(),
+        msg: to_json_binary(&Cw20ExecuteMsg::Transfer { recipient: USER1.to_string(), amount: Uint128::from(BASE_AMOUNT * 2) }).unwrap(),
+        funds: vec![],
+    }));
+}
+
+fn use_token() -> ExecuteMsg {
+    ExecuteMsg::SetCw20Token { token: Some(TOKEN.to_string()), base: None }
+}
+
+fn token_info() -> MessageInfo {
+    sender(TOKEN)
+}
+
+fn receive_msg(sender: &str, amount: u128) -> ExecuteMsg {
+    ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: sender.to_string(),
+        amount: Uint128::from(amount),
+        msg: to_json_binary(&Cw20HookMsg::Deposit {}).unwrap(),
+    })
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use cosmwasm_std::{testing::mock_env, Addr, Binary};
+use dd_blind_box::{
+    commitment::reveal_sign_digest,
+    contract::{execute, query},
+    error::ContractError,
+    msg::{ExecuteMsg, QueryMsg, RevealDelegateMsg, RevealDelegateResponse, UserInfoResponse},
+    state::{RevealDelegate, Scale, VoteState},
+};
+use ed25519_zebra::{SigningKey, VerificationKey};
+use common::*;
+
+
+#[test]
+fn test_relayer_reveals_for_voter() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    commit_as(&mut deps, USER1);
+    let (msg, info) = create_set_vote_state_msg(VoteState::Reveal);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // 未设置委托时不可代为揭示
+    let result = execute(deps.as_mut(), env.clone(), sender(OPERATOR), reveal_for(USER1, "r", None));
+    assert_eq!(result.unwrap_err(), ContractError::Unauthorized);
+
+    set_delegate(&mut deps, USER1, RevealDelegateMsg::Relayer { address: OPERATOR.to_string() });
+
+    // 只有登记的 relayer 可代为揭示
+    let result = execute(deps.as_mut(), env.clone(), sender(USER2), reveal_for(USER1, "r", None));
+    assert_eq!(result.unwrap_err(), ContractError::Unauthorized);
+
+    // 揭示值须与投票者本人的承诺一致
+    let result = execute(deps.as_mut(), env.clone(), sender(OPERATOR), reveal_for(USER1, "x", None));
+    assert!(result.unwrap_err().to_string().contains("commitment mismatch"));
+
+    let res = execute(deps.as_mut(), env, sender(OPERATOR), reveal_for(USER1, "r", None)).unwrap();
+    assert_eq!(res.attributes.iter().find(|a| a.key == "voter").unwrap().value, USER1);
+    assert_eq!(res.attributes.iter().find(|a| a.key == "relayer").unwrap().value, OPERATOR);
+    assert!(revealed(&deps, USER1));
+}
+
+#[test]
+fn test_ed25519_signed_reveal() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    commit_as(&mut deps, USER1);
+
+    let sk = SigningKey::from([7u8; 32]);
+    let pubkey: [u8; 32] = VerificationKey::from(&sk).into();
+    set_delegate(&mut deps, USER1, RevealDelegateMsg::Ed25519 { pubkey: Binary::from(pubkey) });
+    let (msg, info) = create_set_vote_state_msg(VoteState::Reveal);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let env = mock_env();
+    let sign = |reveal: &str| {
+        let digest = reveal_sign_digest(&env.block.chain_id, env.contract.address.as_str(), 0, USER1, reveal, "s");
+        Binary::from(sk.sign(&digest).to_bytes())
+    };
+
+    // 缺少签名、签名与揭示内容不符均被拒绝
+    let result = execute(deps.as_mut(), env.clone(), sender(USER2), reveal_for(USER1, "r", None));
+    assert_eq!(result.unwrap_err(), ContractError::InvalidSignature);
+    let result = execute(deps.as_mut(), env.clone(), sender(USER2), reveal_for(USER1, "r", Some(sign("x"))));
+    assert_eq!(result.unwrap_err(), ContractError::InvalidSignature);
+    let result = execute(deps.as_mut(), env.clone(), sender(USER2), reveal_for(USER1, "r", Some(Binary::from(vec![0u8; 3]))));
+    assert_eq!(result.unwrap_err(), ContractError::InvalidSignature);
+
+    // 任何人都可以提交有效签名
+    execute(deps.as_mut(), env, sender(USER2), reveal_for(USER1, "r", Some(sign("r")))).unwrap();
+    assert!(revealed(&deps, USER1));
+    assert!(!revealed(&deps, USER2));
+}
+
+#[test]
+fn test_delegate_query_and_removal() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let result = execute(deps.as_mut(), env.clone(), sender(USER1), ExecuteMsg::SetRevealDelegate {
+        delegate: Some(RevealDelegateMsg::Relayer { address: "not-an-address".to_string() }),
+    });
+    assert!(result.is_err());
+
+    set_delegate(&mut deps, USER1, RevealDelegateMsg::Relayer { address: OPERATOR.to_string() });
+    assert_eq!(delegate_of(&deps, USER1), Some(RevealDelegate::Relayer { address: Addr::unchecked(OPERATOR) }));
+
+    execute(deps.as_mut(), env, sender(USER1), ExecuteMsg::SetRevealDelegate { delegate: None }).unwrap();
+    assert_eq!(delegate_of(&deps, USER1), None);
+}
+
+fn reveal_for(voter: &str, reveal: &str, signature: Option<Binary>) -> ExecuteMsg {
+    ExecuteMsg::RevealFor { voter: voter.to_string(), reveal: reveal.to_string(), salt: "s".to_string(), signature }
+}
+
+fn commit_as(deps: &mut MockDeps, voter: &str) {
+    let msg = ExecuteMsg::CommitVote { commitment: calculate_commitment(voter, "r", "s") };
+    execute(deps.as_mut(), mock_env(), sender(voter), msg).unwrap();
+}
+
+fn set_delegate(deps: &mut MockDeps, voter: &str, delegate: RevealDelegateMsg) {
+    execute(deps.as_mut(), mock_env(), sender(voter), ExecuteMsg::SetRevealDelegate { delegate: Some(delegate) }).unwrap();
+}
+
+fn delegate_of(deps: &MockDeps, voter: &str) -> Option<RevealDelegate> {
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::RevealDelegate { voter: voter.to_string() }).unwrap();
+    cosmwasm_std::from_json::<RevealDelegateResponse>(res).unwrap().delegate
+}
+
+fn revealed(deps: &MockDeps, voter: &str) -> bool {
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::UserInfo { address: voter.to_string() }).unwrap();
+    cosmwasm_std::from_json::<UserInfoResponse>(res).unwrap().revealed
+}
@@ -1,6 +1,6 @@
 mod common;
 
-use cosmwasm_std::{coins, MessageInfo};
+use cosmwasm_std::{coins, BankMsg, CosmosMsg, MessageInfo};
 use dd_blind_box::{
     contract::execute,
     error::ContractError,
@@ -225,9 +225,14 @@ fn test_deposit_fractional_base_amount() {
     let minted_attr = res.attributes.iter().find(|a| a.key == "minted").unwrap();
     assert_eq!(minted_attr.value, "1");
     
-    // 验证充值记录包含完整金额
+    // 只记录实际成交金额，多付部分退还
+    assert_eq!(res.attributes.iter().find(|a| a.key == "refund").unwrap().value, (BASE_AMOUNT / 2).to_string());
+    assert_eq!(res.messages[1].msg, CosmosMsg::Bank(BankMsg::Send {
+        to_address: USER1.to_string(),
+        amount: coins(BASE_AMOUNT / 2, BASE_DENOM),
+    }));
     let deposit = query_deposit_test(&deps, USER1);
-    assert_eq!(deposit.principal, (BASE_AMOUNT + BASE_AMOUNT / 2).to_string());
+    assert_eq!(deposit.principal, BASE_AMOUNT.to_string());
 }
 
 #[test]
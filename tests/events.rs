@@ -0,0 +1,83 @@
+mod common;
+
+use cosmwasm_std::{Addr, Event, Response};
+use dd_blind_box::{
+    contract::execute,
+    events::{self, keys, EVENT_SCHEMA_VERSION},
+    state::{RevealInfo, Scale, VoteState, CONFIG, REVEALS},
+};
+use common::*;
+
+
+#[test]
+fn test_deposit_event() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let (msg, info) = create_deposit_msg(BASE_AMOUNT);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    let (msg, info) = create_deposit_msg(BASE_AMOUNT * 2);
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    let event = find_event(&res, events::DEPOSIT);
+    assert_eq!(attr_of(event, keys::VERSION), EVENT_SCHEMA_VERSION);
+    assert_eq!(attr_of(event, keys::SENDER), USER1);
+    assert_eq!(attr_of(event, keys::AMOUNT), format!("{}{}", BASE_AMOUNT * 2, BASE_DENOM));
+    assert_eq!(attr_of(event, keys::FIRST_TOKEN_ID), "1");
+    assert_eq!(attr_of(event, keys::MINTED), "2");
+}
+
+#[test]
+fn test_commit_reveal_and_phase_events() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let commitment = calculate_commitment(USER1, "my_reveal", "my_salt");
+    let (msg, info) = create_commit_msg(commitment.clone());
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    let event = find_event(&res, events::COMMIT);
+    assert_eq!(attr_of(event, keys::VOTER), USER1);
+    assert_eq!(attr_of(event, keys::COMMITMENT), commitment);
+
+    let (msg, info) = create_set_vote_state_msg(VoteState::Reveal);
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    let event = find_event(&res, events::PHASE_CHANGE);
+    assert_eq!(attr_of(event, keys::FROM), "commit");
+    assert_eq!(attr_of(event, keys::TO), "reveal");
+
+    let (msg, info) = create_reveal_msg("my_reveal".to_string(), "my_salt".to_string());
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(attr_of(find_event(&res, events::REVEAL), keys::VOTER), USER1);
+}
+
+#[test]
+fn test_finalize_emits_tier_and_payout_events() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let (msg, info) = create_deposit_msg(BASE_AMOUNT);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    REVEALS.save(&mut deps.storage, (0, &Addr::unchecked(USER1)), &RevealInfo { reveal: "r".to_string(), salt: "s".to_string() }).unwrap();
+    let mut config = CONFIG.load(&deps.storage).unwrap();
+    config.vote_state = VoteState::Closed;
+    CONFIG.save(&mut deps.storage, &config).unwrap();
+
+    let (msg, info) = create_finalize_msg();
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    let event = find_event(&res, events::TIER_ASSIGNED);
+    assert_eq!(attr_of(event, keys::ADDRESS), USER1);
+    assert_eq!(attr_of(event, keys::TIER), "1");
+
+    let event = find_event(&res, events::PAYOUT);
+    assert_eq!(attr_of(event, keys::RECIPIENT), USER1);
+    assert_eq!(attr_of(event, keys::AMOUNT), format!("{}{}", BASE_AMOUNT * 2, BASE_DENOM));
+}
+
+fn find_event<'a>(res: &'a Response, ty: &str) -> &'a Event {
+    res.events.iter().find(|e| e.ty == ty).unwrap_or_else(|| panic!("missing event {}", ty))
+}
+
+fn attr_of(event: &Event, key: &str) -> String {
+    event.attributes.iter().find(|a| a.key == key).unwrap().value.clone()
+}
@@ -69,6 +69,7 @@ fn test_instantiate_different_base_coins() {
             scale: Scale::Tiny,
             base: base.clone(),
             first_prize_count: None,
+            total_supply: None,
         };
         
         let result = instantiate(deps.as_mut(), env.clone(), info, msg);
@@ -95,6 +96,7 @@ fn test_instantiate_zero_amount() {
             amount: Uint128::zero(),
         },
         first_prize_count: None,
+        total_supply: None,
     };
     
     let result = instantiate(deps.as_mut(), env, info, msg);
@@ -0,0 +1,120 @@
+mod common;
+
+use cosmwasm_std::{coins, Addr, Coin, MessageInfo, Uint128};
+use dd_blind_box::{
+    contract::{execute, query},
+    msg::{AllCommitsResponse, AllDepositsResponse, AllRevealsResponse, ExecuteMsg, QueryMsg, TopDepositorsResponse},
+    state::{PriceCurve, Scale, VoteState},
+};
+use common::*;
+
+
+#[test]
+fn test_all_deposits_paginates_by_address() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    let mut users: Vec<Addr> = [USER1, USER2, USER3].into_iter().map(Addr::unchecked).collect();
+    for user in &users {
+        execute(deps.as_mut(), env.clone(), funded(user.as_str(), BASE_AMOUNT), ExecuteMsg::Deposit {}).unwrap();
+    }
+    users.sort();
+
+    let page: AllDepositsResponse = query_json(&deps, &env, QueryMsg::AllDeposits { start_after: None, limit: Some(2) });
+    let addrs: Vec<String> = page.deposits.iter().map(|d| d.address.clone()).collect();
+    assert_eq!(addrs, vec![users[0].to_string(), users[1].to_string()]);
+    assert_eq!(page.deposits[0].principal, Uint128::from(BASE_AMOUNT));
+
+    let page: AllDepositsResponse = query_json(&deps, &env, QueryMsg::AllDeposits { start_after: Some(addrs[1].clone()), limit: Some(2) });
+    assert_eq!(page.deposits.len(), 1);
+    assert_eq!(page.deposits[0].address, users[2].to_string());
+
+    // 超出上限的 limit 被截断
+    let page: AllDepositsResponse = query_json(&deps, &env, QueryMsg::AllDeposits { start_after: None, limit: Some(10_000) });
+    assert_eq!(page.deposits.len(), 3);
+}
+
+#[test]
+fn test_all_commits_and_reveals() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    let user1 = Addr::unchecked(USER1);
+    let user2 = Addr::unchecked(USER2);
+    for user in [&user1, &user2] {
+        let commitment = calculate_commitment(user.as_str(), "r", "s");
+        execute(deps.as_mut(), env.clone(), sender(user.as_str()), ExecuteMsg::CommitVote { commitment }).unwrap();
+    }
+    let (msg, info) = create_set_vote_state_msg(VoteState::Reveal);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    execute(deps.as_mut(), env.clone(), sender(user2.as_str()), ExecuteMsg::RevealVote { reveal: "r".to_string(), salt: "s".to_string() }).unwrap();
+
+    let commits: AllCommitsResponse = query_json(&deps, &env, QueryMsg::AllCommits { start_after: None, limit: None });
+    assert_eq!(commits.commits.len(), 2);
+    assert!(commits.commits.iter().any(|c| c.address == user1.as_str() && c.commitment == calculate_commitment(user1.as_str(), "r", "s")));
+
+    let reveals: AllRevealsResponse = query_json(&deps, &env, QueryMsg::AllReveals { start_after: None, limit: None });
+    assert_eq!(reveals.reveals.len(), 1);
+    assert_eq!(reveals.reveals[0].address, user2.to_string());
+    assert_eq!(reveals.reveals[0].salt, "s");
+}
+
+#[test]
+fn test_top_depositors_ordered_by_principal() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    let user1 = Addr::unchecked(USER1);
+    let user2 = Addr::unchecked(USER2);
+    let user3 = Addr::unchecked(USER3);
+
+    execute(deps.as_mut(), env.clone(), funded(user1.as_str(), BASE_AMOUNT), ExecuteMsg::Deposit {}).unwrap();
+    execute(deps.as_mut(), env.clone(), funded(user2.as_str(), BASE_AMOUNT * 2), ExecuteMsg::Deposit {}).unwrap();
+    execute(deps.as_mut(), env.clone(), funded(user3.as_str(), BASE_AMOUNT), ExecuteMsg::Deposit {}).unwrap();
+    // 再次充值后排名上移，旧索引不残留
+    execute(deps.as_mut(), env.clone(), funded(user3.as_str(), BASE_AMOUNT * 2), ExecuteMsg::Deposit {}).unwrap();
+
+    let top: TopDepositorsResponse = query_json(&deps, &env, QueryMsg::TopDepositors { limit: Some(2) });
+    let ranked: Vec<(String, Uint128)> = top.depositors.iter().map(|d| (d.address.clone(), d.principal)).collect();
+    assert_eq!(ranked, vec![
+        (user3.to_string(), Uint128::from(BASE_AMOUNT * 3)),
+        (user2.to_string(), Uint128::from(BASE_AMOUNT * 2)),
+    ]);
+
+    let top: TopDepositorsResponse = query_json(&deps, &env, QueryMsg::TopDepositors { limit: None });
+    assert_eq!(top.depositors.len(), 3);
+}
+
+#[test]
+fn test_extra_denom_depositors_listed_but_not_ranked() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    let extra_prices = vec![Coin { denom: "ibc/USDC".to_string(), amount: Uint128::from(5u128) }];
+    execute(deps.as_mut(), env.clone(), owner_info(), ExecuteMsg::SetPricing { extra_prices, bulk_discounts: vec![], curve: PriceCurve::Flat }).unwrap();
+
+    execute(deps.as_mut(), env.clone(), funded(USER1, BASE_AMOUNT), ExecuteMsg::Deposit {}).unwrap();
+    let info = MessageInfo { sender: Addr::unchecked(USER2), funds: coins(10, "ibc/USDC") };
+    execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Deposit {}).unwrap();
+
+    // 仅以其他币种充值的 USER2 同样出现在 AllDeposits 中
+    let page: AllDepositsResponse = query_json(&deps, &env, QueryMsg::AllDeposits { start_after: None, limit: None });
+    let entry = page.deposits.iter().find(|d| d.address == USER2).unwrap();
+    assert_eq!(entry.principal, Uint128::zero());
+    assert_eq!(entry.others, coins(10, "ibc/USDC"));
+    assert_eq!(page.deposits.len(), 2);
+
+    // 翻页时不重复、不遗漏
+    let first: AllDepositsResponse = query_json(&deps, &env, QueryMsg::AllDeposits { start_after: None, limit: Some(1) });
+    let second: AllDepositsResponse = query_json(&deps, &env, QueryMsg::AllDeposits { start_after: Some(first.deposits[0].address.clone()), limit: Some(1) });
+    assert_eq!(vec![first.deposits[0].clone(), second.deposits[0].clone()], page.deposits);
+
+    // 排名只按 base 本金
+    let top: TopDepositorsResponse = query_json(&deps, &env, QueryMsg::TopDepositors { limit: None });
+    assert_eq!(top.denom, BASE_DENOM);
+    assert_eq!(top.depositors.iter().map(|d| d.address.as_str()).collect::<Vec<_>>(), vec![USER1]);
+}
+
+fn query_json<T: serde::de::DeserializeOwned>(
+    deps: &MockDeps,
+    env: &cosmwasm_std::Env,
+    msg: QueryMsg,
+) -> T {
+    cosmwasm_std::from_json(query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap()
+}
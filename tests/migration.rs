@@ -9,8 +9,8 @@ use dd_blind_box::{
 use common::*;
 
 // Test constants
-const OWNER: &str = "owner";
-const USER1: &str = "user1";
+const OWNER: &str = "cosmwasm1fsgzj6t7udv8zhf6zj32mkqhcjcpv52yph5qsdcl0qt94jgdckqs2g053y"; // addr_make("owner")
+const USER1: &str = "cosmwasm1pgzph9rze2j2xxavx4n7pdhxlkgsq7rak245x0vk7mgh3j4le6gqmlwcfu"; // addr_make("user1")
 const BASE_DENOM: &str = "ujunox";
 const BASE_AMOUNT: u128 = 100;
 
@@ -38,6 +38,7 @@ fn instantiate_contract(
             amount: cosmwasm_std::Uint128::from(base_amount),
         },
         first_prize_count: None,
+        total_supply: None,
     };
     dd_blind_box::contract::instantiate(deps.as_mut(), env.clone(), info, msg)
 }
@@ -82,7 +83,7 @@ fn test_migrate_success() {
     assert_eq!(config_before.total_supply, 10);
     
     // 迁移到Small规模
-    let msg = MigrateMsg {};
+    let msg = MigrateMsg::default();
     let result = migrate(deps.as_mut(), env, msg);
     assert!(result.is_ok());
     
@@ -114,7 +115,7 @@ fn test_migrate_all_scales() {
         instantiate_contract(&mut deps, &env, scale.clone(), BASE_AMOUNT).unwrap();
         
         // 执行空置迁移
-        let msg = MigrateMsg {};
+        let msg = MigrateMsg::default();
         let result = migrate(deps.as_mut(), env, msg);
         assert!(result.is_ok());
         
@@ -133,7 +134,7 @@ fn test_migrate_same_scale() {
     instantiate_contract(&mut deps, &env, Scale::Medium, BASE_AMOUNT).unwrap();
     
     // 迁移到相同的Medium规模
-    let msg = MigrateMsg {};
+    let msg = MigrateMsg::default();
     let result = migrate(deps.as_mut(), env, msg);
     assert!(result.is_ok());
     
@@ -170,7 +171,7 @@ fn test_migrate_preserves_state() {
     let deposit_before = query_deposit_test(&deps, USER1);
     
     // 迁移到Large规模
-    let msg = MigrateMsg {};
+    let msg = MigrateMsg::default();
     let result = migrate(deps.as_mut(), env, msg);
     assert!(result.is_ok());
     
@@ -199,7 +200,7 @@ fn test_migrate_scale_up() {
     instantiate_contract(&mut deps, &env, Scale::Small, BASE_AMOUNT).unwrap();
     
     // 迁移到Huge规模
-    let msg = MigrateMsg {};
+    let msg = MigrateMsg::default();
     let result = migrate(deps.as_mut(), env, msg);
     assert!(result.is_ok());
     
@@ -217,7 +218,7 @@ fn test_migrate_scale_down() {
     instantiate_contract(&mut deps, &env, Scale::Large, BASE_AMOUNT).unwrap();
     
     // 迁移到Medium规模
-    let msg = MigrateMsg {};
+    let msg = MigrateMsg::default();
     let result = migrate(deps.as_mut(), env, msg);
     assert!(result.is_ok());
     
@@ -245,7 +246,7 @@ fn test_migrate_with_existing_nfts() {
     }
     
     // 迁移到Medium规模
-    let msg = MigrateMsg {};
+    let msg = MigrateMsg::default();
     let result = migrate(deps.as_mut(), env.clone(), msg);
     assert!(result.is_ok());
     
@@ -276,7 +277,7 @@ fn test_migrate_with_voting_data() {
     setup_voting_data(&mut deps);
     
     // 迁移到Large规模
-    let msg = MigrateMsg {};
+    let msg = MigrateMsg::default();
     let result = migrate(deps.as_mut(), env, msg);
     assert!(result.is_ok());
     
@@ -297,7 +298,7 @@ fn test_migrate_response_attributes() {
     instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
     
     // 迁移到Small规模
-    let msg = MigrateMsg {};
+    let msg = MigrateMsg::default();
     let result = migrate(deps.as_mut(), env, msg);
     assert!(result.is_ok());
     
@@ -317,7 +318,7 @@ fn test_migrate_multiple_times() {
     instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
     
     // 第一次迁移
-    let msg = MigrateMsg {};
+    let msg = MigrateMsg::default();
     let result = migrate(deps.as_mut(), env.clone(), msg);
     assert!(result.is_ok());
     
@@ -326,7 +327,7 @@ fn test_migrate_multiple_times() {
     assert_eq!(config.total_supply, 10);    // 保持不变
     
     // 第二次迁移
-    let msg = MigrateMsg {};
+    let msg = MigrateMsg::default();
     let result = migrate(deps.as_mut(), env.clone(), msg);
     assert!(result.is_ok());
     
@@ -335,7 +336,7 @@ fn test_migrate_multiple_times() {
     assert_eq!(config.total_supply, 10);    // 保持不变
     
     // 第三次迁移
-    let msg = MigrateMsg {};
+    let msg = MigrateMsg::default();
     let result = migrate(deps.as_mut(), env, msg);
     assert!(result.is_ok());
     
@@ -354,12 +355,12 @@ fn setup_voting_data(deps: &mut OwnedDeps<cosmwasm_std::MemoryStorage, cosmwasm_
     CONFIG.save(&mut deps.storage, &config).unwrap();
     
     // 设置投票承诺
-    COMMITS.save(&mut deps.storage, cosmwasm_std::Addr::unchecked(USER1), &dd_blind_box::state::CommitInfo {
+    COMMITS.save(&mut deps.storage, (config.round, &cosmwasm_std::Addr::unchecked(USER1)), &dd_blind_box::state::CommitInfo {
         commitment: "test_commitment".to_string(),
     }).unwrap();
     
     // 设置投票揭示
-    REVEALS.save(&mut deps.storage, cosmwasm_std::Addr::unchecked(USER1), &dd_blind_box::state::RevealInfo {
+    REVEALS.save(&mut deps.storage, (config.round, &cosmwasm_std::Addr::unchecked(USER1)), &dd_blind_box::state::RevealInfo {
         reveal: "test_reveal".to_string(),
         salt: "test_salt".to_string(),
     }).unwrap();
@@ -0,0 +1,82 @@
+mod common;
+
+use cosmwasm_std::{Addr, Uint128};
+use cw2::set_contract_version;
+use dd_blind_box::{
+    contract::{execute, migrate},
+    error::ContractError,
+    migrations::{self, LEGACY_COMMITS, LEGACY_TIERS},
+    msg::MigrateMsg,
+    state::{CommitInfo, PhaseWindow, Scale, COMMITS, RESOLVED_PRINCIPAL, SETTLED, STORAGE_VERSION, TIERS, TIER_COUNTS, TOTAL_PRINCIPAL},
+};
+use common::*;
+
+
+#[test]
+fn test_migrate_rejects_other_contract() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    set_contract_version(&mut deps.storage, "crates.io:other_contract", "0.1.0").unwrap();
+
+    let result = migrate(deps.as_mut(), env, MigrateMsg::default());
+    assert_eq!(result.unwrap_err(), ContractError::InvalidContractName { previous: "crates.io:other_contract".to_string() });
+}
+
+#[test]
+fn test_migrate_rejects_downgrade() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    set_contract_version(&mut deps.storage, "crates.io:dd_blind_box", "99.0.0").unwrap();
+
+    let result = migrate(deps.as_mut(), env, MigrateMsg::default());
+    assert!(matches!(result.unwrap_err(), ContractError::CannotDowngrade { .. }));
+}
+
+#[test]
+fn test_migrate_runs_pending_steps() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    let (msg, info) = create_deposit_msg(BASE_AMOUNT * 3);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // 模拟引入存储版本之前的旧部署
+    STORAGE_VERSION.remove(&mut deps.storage);
+    TOTAL_PRINCIPAL.remove(&mut deps.storage, BASE_DENOM);
+    LEGACY_TIERS.save(&mut deps.storage, Addr::unchecked(USER1), &2).unwrap();
+    LEGACY_COMMITS.save(&mut deps.storage, Addr::unchecked(USER1), &CommitInfo { commitment: "c".to_string() }).unwrap();
+
+    let res = migrate(deps.as_mut(), env.clone(), MigrateMsg::default()).unwrap();
+    assert_eq!(res.attributes.iter().find(|a| a.key == "steps").unwrap().value, "backfill_total_principal,backfill_settled,rewrite_config,backfill_voter_counts,backfill_tier_index,backfill_deposit_rank,backfill_settlement_epoch,key_round_maps");
+    assert_eq!(TOTAL_PRINCIPAL.load(&deps.storage, BASE_DENOM).unwrap(), Uint128::from(BASE_AMOUNT * 3));
+    assert!(SETTLED.load(&deps.storage).unwrap());
+    assert_eq!(RESOLVED_PRINCIPAL.load(&deps.storage, BASE_DENOM).unwrap(), Uint128::from(BASE_AMOUNT * 3));
+    // 单轮布局的记录移入当前轮次（0）的分区
+    assert_eq!(TIER_COUNTS.load(&deps.storage, (0, 2)).unwrap(), 1);
+    assert_eq!(TIERS.load(&deps.storage, (0, &Addr::unchecked(USER1))).unwrap(), 2);
+    assert!(COMMITS.has(&deps.storage, (0, &Addr::unchecked(USER1))));
+    assert!(LEGACY_TIERS.is_empty(&deps.storage));
+    assert!(LEGACY_COMMITS.is_empty(&deps.storage));
+    assert_eq!(STORAGE_VERSION.load(&deps.storage).unwrap(), migrations::latest_storage_version());
+
+    // 再次迁移不会重复执行
+    let res = migrate(deps.as_mut(), env, MigrateMsg::default()).unwrap();
+    assert_eq!(res.attributes[1].value, "Migration completed - no changes applied");
+}
+
+#[test]
+fn test_migrate_applies_config_params() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let msg = MigrateMsg {
+        owner: Some(USER2.to_string()),
+        paused: Some(true),
+        first_prize_count: None,
+        sale_window: Some(PhaseWindow { end_height: Some(100), ..PhaseWindow::default() }),
+    };
+    let res = migrate(deps.as_mut(), env, msg).unwrap();
+    assert_eq!(res.attributes.iter().find(|a| a.key == "config_fields").unwrap().value, "owner,paused,sale_window");
+
+    let config = query_config(&deps);
+    assert_eq!(config.owner, USER2);
+}
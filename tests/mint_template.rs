@@ -0,0 +1,116 @@
+mod common;
+
+use dd_blind_box::{
+    contract::{execute, query},
+    error::ContractError,
+    msg::{ExecuteMsg, PreviewMintResponse, QueryMsg},
+    state::{MintTemplate, Scale, SkuRule},
+};
+use luckee_nft::types::NftKind;
+use common::*;
+
+
+#[test]
+fn test_default_template_matches_legacy_meta() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let preview = preview_mint(&deps, 7);
+    assert_eq!(preview.token_id, 7);
+    assert_eq!(preview.extension.kind, NftKind::Clover);
+    assert_eq!(preview.extension.series_id, "blind_box_tiny");
+    assert_eq!(preview.extension.collection_group_id, Some("group_0".to_string()));
+    assert_eq!(preview.extension.physical_sku, None);
+    assert_eq!(preview.extension.serial_in_series, 7);
+}
+
+#[test]
+fn test_set_mint_template_applies_to_preview() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Small, BASE_AMOUNT).unwrap();
+
+    let template = MintTemplate {
+        kind: NftKind::Clover,
+        series_pattern: "spring_{scale}_drop".to_string(),
+        group_size: 10,
+        skus: vec![
+            SkuRule { start_id: 0, end_id: 4, sku: "SKU-GOLD".to_string() },
+            SkuRule { start_id: 5, end_id: 49, sku: "SKU-SILVER".to_string() },
+        ],
+    };
+    let res = execute(deps.as_mut(), env, owner_info(), ExecuteMsg::SetMintTemplate { template: template.clone() }).unwrap();
+    assert_eq!(res.attributes[0].value, "set_mint_template");
+    assert_eq!(query_config(&deps).mint_template, template);
+
+    let preview = preview_mint(&deps, 3);
+    assert_eq!(preview.extension.series_id, "spring_small_drop");
+    assert_eq!(preview.extension.collection_group_id, Some("group_0".to_string()));
+    assert_eq!(preview.extension.physical_sku, Some("SKU-GOLD".to_string()));
+
+    let preview = preview_mint(&deps, 23);
+    assert_eq!(preview.extension.collection_group_id, Some("group_2".to_string()));
+    assert_eq!(preview.extension.physical_sku, Some("SKU-SILVER".to_string()));
+
+    let preview = preview_mint(&deps, 80);
+    assert_eq!(preview.extension.physical_sku, None);
+}
+
+#[test]
+fn test_zero_group_size_disables_grouping() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let template = MintTemplate { group_size: 0, ..MintTemplate::default() };
+    execute(deps.as_mut(), env, owner_info(), ExecuteMsg::SetMintTemplate { template }).unwrap();
+
+    assert_eq!(preview_mint(&deps, 1).extension.collection_group_id, None);
+}
+
+#[test]
+fn test_set_mint_template_validation() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let template = MintTemplate { series_pattern: String::new(), ..MintTemplate::default() };
+    assert!(execute(deps.as_mut(), env.clone(), owner_info(), ExecuteMsg::SetMintTemplate { template }).is_err());
+
+    let template = MintTemplate {
+        skus: vec![SkuRule { start_id: 5, end_id: 1, sku: "SKU".to_string() }],
+        ..MintTemplate::default()
+    };
+    assert!(execute(deps.as_mut(), env, owner_info(), ExecuteMsg::SetMintTemplate { template }).is_err());
+}
+
+#[test]
+fn test_set_mint_template_unauthorized() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let info = sender(USER1);
+    let result = execute(deps.as_mut(), env, info, ExecuteMsg::SetMintTemplate { template: MintTemplate::default() });
+    assert_eq!(result.unwrap_err(), ContractError::Unauthorized);
+}
+
+#[test]
+fn test_deposit_uses_mint_template() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let template = MintTemplate { series_pattern: "custom".to_string(), ..MintTemplate::default() };
+    execute(deps.as_mut(), env.clone(), owner_info(), ExecuteMsg::SetMintTemplate { template }).unwrap();
+
+    let (msg, info) = create_deposit_msg(BASE_AMOUNT);
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    let cosmwasm_std::CosmosMsg::Wasm(cosmwasm_std::WasmMsg::Execute { msg, .. }) = &res.messages[0].msg else {
+        panic!("expected wasm execute");
+    };
+    let luckee_nft::msg::ExecuteMsg::BatchMint { mints } = cosmwasm_std::from_json(msg).unwrap() else {
+        panic!("expected batch mint");
+    };
+    assert_eq!(mints[0].extension.series_id, "custom");
+}
+
+fn preview_mint(deps: &MockDeps, token_id: u64) -> PreviewMintResponse {
+    let res = query(deps.as_ref(), cosmwasm_std::testing::mock_env(), QueryMsg::PreviewMint { token_id }).unwrap();
+    cosmwasm_std::from_json(res).unwrap()
+}
@@ -0,0 +1,198 @@
+//! 端到端测试：在 cw-multi-test 中部署盲盒与 luckee_nft 合约，子消息真实执行
+mod common;
+
+use cosmwasm_std::{coins, Addr, Empty, Uint128};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+use dd_blind_box::{
+    contract::{execute, instantiate, query, reply},
+    msg::{ComputeCommitmentResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg, TierResponse},
+    state::{Scale, VoteState},
+};
+use serde::{Deserialize, Serialize};
+use common::*;
+
+/// luckee_nft 所有者查询（仅包含用到的字段）
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum NftQuery {
+    OwnerOf { token_id: u64 },
+}
+
+#[derive(Deserialize)]
+struct NftOwner {
+    owner: String,
+}
+
+const START_BALANCE: u128 = 10_000;
+/// 拥有者注入的奖池，用于支付高于本金的返还
+const PRIZE_POOL: u128 = 1_000;
+
+struct Suite {
+    app: App,
+    owner: Addr,
+    blind_box: Addr,
+    nft: Addr,
+}
+
+fn blind_box_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query).with_reply(reply))
+}
+
+fn luckee_nft_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(luckee_nft::contract::execute, luckee_nft::contract::instantiate, luckee_nft::contract::query))
+}
+
+/// 部署盲盒合约，并通过 `InstantiateNftContract` 由盲盒合约实例化 luckee_nft
+fn setup(users: &[&str]) -> Suite {
+    let mut app = App::default();
+    let owner = app.api().addr_make("owner");
+    let funded: Vec<Addr> = users.iter().map(|u| app.api().addr_make(u)).chain([owner.clone()]).collect();
+    app.init_modules(|router, _, storage| {
+        for addr in &funded {
+            router.bank.init_balance(storage, addr, coins(START_BALANCE, BASE_DENOM)).unwrap();
+        }
+    });
+
+    let blind_box_code = app.store_code(blind_box_contract());
+    let nft_code = app.store_code(luckee_nft_contract());
+    let msg = InstantiateMsg {
+        scale: Scale::Tiny,
+        base: cosmwasm_std::Coin { denom: BASE_DENOM.to_string(), amount: Uint128::from(BASE_AMOUNT) },
+        first_prize_count: None,
+        total_supply: None,
+    };
+    let blind_box = app.instantiate_contract(blind_box_code, owner.clone(), &msg, &[], "dd_blind_box", Some(owner.to_string())).unwrap();
+
+    app.execute_contract(owner.clone(), blind_box.clone(), &ExecuteMsg::SetNftCodeId { code_id: nft_code }, &[]).unwrap();
+    let msg = ExecuteMsg::InstantiateNftContract { name: "Luckee".to_string(), symbol: "LUCK".to_string(), base_uri: None };
+    app.execute_contract(owner.clone(), blind_box.clone(), &msg, &[]).unwrap();
+
+    let config: ConfigResponse = app.wrap().query_wasm_smart(&blind_box, &QueryMsg::Config {}).unwrap();
+    let nft = Addr::unchecked(config.nft_contract.expect("reply records the instantiated NFT contract"));
+    Suite { app, owner, blind_box, nft }
+}
+
+impl Suite {
+    fn user(&self, name: &str) -> Addr {
+        self.app.api().addr_make(name)
+    }
+
+    fn balance(&self, addr: &Addr) -> u128 {
+        self.app.wrap().query_balance(addr, BASE_DENOM).unwrap().amount.u128()
+    }
+
+    fn nft_owner(&self, token_id: u64) -> String {
+        let res: NftOwner = self.app.wrap().query_wasm_smart(&self.nft, &NftQuery::OwnerOf { token_id }).unwrap();
+        res.owner
+    }
+
+    fn set_vote_state(&mut self, state: VoteState) {
+        self.app.execute_contract(self.owner.clone(), self.blind_box.clone(), &ExecuteMsg::SetVoteState { state }, &[]).unwrap();
+    }
+
+    fn commit(&mut self, voter: &Addr) {
+        let query = QueryMsg::ComputeCommitment { voter: voter.to_string(), reveal: "r".to_string(), salt: "s".to_string() };
+        let computed: ComputeCommitmentResponse = self.app.wrap().query_wasm_smart(&self.blind_box, &query).unwrap();
+        let msg = ExecuteMsg::CommitVote { commitment: computed.commitment };
+        self.app.execute_contract(voter.clone(), self.blind_box.clone(), &msg, &[]).unwrap();
+    }
+
+    fn reveal(&mut self, voter: &Addr) {
+        let msg = ExecuteMsg::RevealVote { reveal: "r".to_string(), salt: "s".to_string() };
+        self.app.execute_contract(voter.clone(), self.blind_box.clone(), &msg, &[]).unwrap();
+    }
+
+    fn tier(&self, voter: &Addr) -> u8 {
+        let res: TierResponse = self.app.wrap().query_wasm_smart(&self.blind_box, &QueryMsg::TierOf { address: voter.to_string() }).unwrap();
+        res.tier
+    }
+}
+
+#[test]
+fn test_instantiate_nft_through_blind_box() {
+    let mut suite = setup(&[]);
+    let config: ConfigResponse = suite.app.wrap().query_wasm_smart(&suite.blind_box, &QueryMsg::Config {}).unwrap();
+    assert_eq!(config.nft_contract, Some(suite.nft.to_string()));
+
+    // 再次实例化被拒绝
+    let msg = ExecuteMsg::InstantiateNftContract { name: "Again".to_string(), symbol: "AGN".to_string(), base_uri: None };
+    assert!(suite.app.execute_contract(suite.owner.clone(), suite.blind_box.clone(), &msg, &[]).is_err());
+}
+
+#[test]
+fn test_set_nft_contract_queries_real_minter() {
+    let mut suite = setup(&[]);
+    let owner = suite.owner.clone();
+    let blind_box = suite.blind_box.clone();
+
+    // 由盲盒合约实例化的 luckee_nft 通过代码 ID 与 Minter 校验
+    let msg = ExecuteMsg::SetNftContract { nft_contract: suite.nft.to_string() };
+    suite.app.execute_contract(owner.clone(), blind_box.clone(), &msg, &[]).unwrap();
+
+    // 铸造者不是盲盒合约的 luckee_nft 被拒绝
+    let nft_code = query_config_of(&suite).nft_code_id.unwrap();
+    let init = luckee_nft::msg::InstantiateMsg {
+        name: "Other".to_string(),
+        symbol: "OTH".to_string(),
+        minter: owner.to_string(),
+        base_uri: None,
+        allowed_instantiators: None,
+    };
+    let other = suite.app.instantiate_contract(nft_code, owner.clone(), &init, &[], "other_nft", None).unwrap();
+    let msg = ExecuteMsg::SetNftContract { nft_contract: other.to_string() };
+    let err = suite.app.execute_contract(owner, blind_box, &msg, &[]).unwrap_err();
+    assert!(err.root_cause().to_string().contains(&format!("minter is {}", suite.owner)));
+}
+
+fn query_config_of(suite: &Suite) -> ConfigResponse {
+    suite.app.wrap().query_wasm_smart(&suite.blind_box, &QueryMsg::Config {}).unwrap()
+}
+
+#[test]
+fn test_deposit_mints_real_nfts() {
+    let mut suite = setup(&["user1", "user2"]);
+    let (user1, user2) = (suite.user("user1"), suite.user("user2"));
+
+    suite.app.execute_contract(user1.clone(), suite.blind_box.clone(), &ExecuteMsg::Deposit {}, &coins(BASE_AMOUNT * 2, BASE_DENOM)).unwrap();
+    suite.app.execute_contract(user2.clone(), suite.blind_box.clone(), &ExecuteMsg::Deposit {}, &coins(BASE_AMOUNT, BASE_DENOM)).unwrap();
+
+    assert_eq!(suite.nft_owner(0), user1.to_string());
+    assert_eq!(suite.nft_owner(1), user1.to_string());
+    assert_eq!(suite.nft_owner(2), user2.to_string());
+    assert_eq!(suite.balance(&user1), START_BALANCE - BASE_AMOUNT * 2);
+    assert_eq!(suite.balance(&suite.blind_box), BASE_AMOUNT * 3);
+}
+
+#[test]
+fn test_full_round_pays_out_by_tier() {
+    let mut suite = setup(&["user1", "user2"]);
+    let (user1, user2) = (suite.user("user1"), suite.user("user2"));
+    let owner = suite.owner.clone();
+    let blind_box = suite.blind_box.clone();
+    suite.app.send_tokens(owner, blind_box.clone(), &coins(PRIZE_POOL, BASE_DENOM)).unwrap();
+
+    let principals = [(user1.clone(), BASE_AMOUNT * 2), (user2.clone(), BASE_AMOUNT)];
+    for (user, amount) in &principals {
+        suite.app.execute_contract(user.clone(), blind_box.clone(), &ExecuteMsg::Deposit {}, &coins(*amount, BASE_DENOM)).unwrap();
+        suite.commit(user);
+    }
+    suite.set_vote_state(VoteState::Reveal);
+    for (user, _) in &principals {
+        suite.reveal(user);
+    }
+    suite.set_vote_state(VoteState::Closed);
+    suite.app.execute_contract(suite.owner.clone(), blind_box.clone(), &ExecuteMsg::Finalize {}, &[]).unwrap();
+
+    // 一等奖 2x、二等奖保本、三等奖 0.5x，返还以银行转账真实到账
+    let mut paid = 0;
+    for (user, principal) in &principals {
+        let payout = match suite.tier(user) {
+            1 => principal * 2,
+            2 => *principal,
+            _ => principal / 2,
+        };
+        paid += payout;
+        assert_eq!(suite.balance(user), START_BALANCE - principal + payout);
+    }
+    assert_eq!(suite.balance(&blind_box), PRIZE_POOL + BASE_AMOUNT * 3 - paid);
+}
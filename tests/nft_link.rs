@@ -0,0 +1,56 @@
+mod common;
+
+use dd_blind_box::{
+    contract::execute,
+    error::ContractError,
+    msg::ExecuteMsg,
+    state::Scale,
+};
+use common::*;
+
+
+fn link() -> ExecuteMsg {
+    ExecuteMsg::SetNftContract { nft_contract: NFT_CONTRACT.to_string() }
+}
+
+#[test]
+fn test_rejects_contract_with_other_minter() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    mock_nft_contract(&mut deps, USER2);
+    let err = execute(deps.as_mut(), env, owner_info(), link()).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidNftContract { .. }));
+}
+
+#[test]
+fn test_rejects_non_contract_and_code_id_mismatch() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    // 普通账户地址没有合约信息
+    let msg = ExecuteMsg::SetNftContract { nft_contract: USER3.to_string() };
+    let err = execute(deps.as_mut(), env.clone(), owner_info(), msg).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidNftContract { .. }));
+
+    execute(deps.as_mut(), env.clone(), owner_info(), ExecuteMsg::SetNftCodeId { code_id: NFT_CODE_ID + 1 }).unwrap();
+    let err = execute(deps.as_mut(), env.clone(), owner_info(), link()).unwrap_err();
+    assert!(err.to_string().contains("code id"));
+
+    execute(deps.as_mut(), env.clone(), owner_info(), ExecuteMsg::SetNftCodeId { code_id: NFT_CODE_ID }).unwrap();
+    execute(deps.as_mut(), env, owner_info(), link()).unwrap();
+}
+
+#[test]
+fn test_relink_locked_after_mint() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    // 铸造前可以重新设置
+    execute(deps.as_mut(), env.clone(), owner_info(), link()).unwrap();
+
+    let (msg, info) = create_deposit_msg(BASE_AMOUNT);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    let err = execute(deps.as_mut(), env, owner_info(), link()).unwrap_err();
+    assert_eq!(err, ContractError::NftContractLocked);
+}
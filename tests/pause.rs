@@ -8,7 +8,6 @@ use dd_blind_box::{
     state::{Scale, VoteState},
 };
 use common::*;
-use sha2::Digest;
 
 
 #[test]
@@ -124,8 +123,7 @@ fn test_pause_voting_allowed() {
     // 计算正确的承诺
     let reveal = "test_reveal";
     let salt = "test_salt";
-    let preimage = format!("{}|{}|{}", USER1, reveal, salt);
-    let commitment = hex::encode(sha2::Sha256::digest(preimage.as_bytes()));
+    let commitment = calculate_commitment(USER1, reveal, salt);
     
     // 投票操作应该仍然被允许
     let (msg, info) = create_commit_msg(commitment);
@@ -383,11 +381,11 @@ fn setup_finalize_environment(deps: &mut OwnedDeps<cosmwasm_std::MemoryStorage,
     let salt = "salt_1";
     let commitment = calculate_commitment(USER1, reveal, salt);
     
-    COMMITS.save(&mut deps.storage, Addr::unchecked(USER1), &dd_blind_box::state::CommitInfo {
+    COMMITS.save(&mut deps.storage, (0, &Addr::unchecked(USER1)), &dd_blind_box::state::CommitInfo {
         commitment,
     }).unwrap();
     
-    REVEALS.save(&mut deps.storage, Addr::unchecked(USER1), &dd_blind_box::state::RevealInfo {
+    REVEALS.save(&mut deps.storage, (0, &Addr::unchecked(USER1)), &dd_blind_box::state::RevealInfo {
         reveal: reveal.to_string(),
         salt: salt.to_string(),
     }).unwrap();
@@ -0,0 +1,205 @@
+mod common;
+
+use cosmwasm_std::{coins, Addr, Coin, MessageInfo, Uint128};
+use dd_blind_box::{
+    contract::{execute, query},
+    error::ContractError,
+    msg::{ExecuteMsg, QueryMsg, QuoteResponse},
+    state::{BulkDiscount, PriceCurve, Scale, EXTRA_DEPOSITS},
+};
+use common::*;
+
+
+const STABLE_DENOM: &str = "ibc/USDC";
+
+#[test]
+fn test_set_pricing_success() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Small, BASE_AMOUNT).unwrap();
+
+    let res = execute(deps.as_mut(), env, owner_info(), ExecuteMsg::SetPricing {
+        extra_prices: vec![Coin { denom: STABLE_DENOM.to_string(), amount: Uint128::from(5u128) }],
+        bulk_discounts: vec![BulkDiscount { min_units: 10, discount_bps: 1_000 }],
+        curve: PriceCurve::Flat,
+    }).unwrap();
+    assert_eq!(res.attributes[1].value, format!("{},{}", BASE_DENOM, STABLE_DENOM));
+
+    let config = query_config(&deps);
+    assert_eq!(config.pricing.extra_prices.len(), 1);
+    assert_eq!(config.pricing.bulk_discounts[0].min_units, 10);
+}
+
+#[test]
+fn test_set_pricing_validation() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Small, BASE_AMOUNT).unwrap();
+
+    // 重复 base 币种
+    let result = execute(deps.as_mut(), env.clone(), owner_info(), ExecuteMsg::SetPricing {
+        extra_prices: vec![Coin { denom: BASE_DENOM.to_string(), amount: Uint128::from(5u128) }],
+        bulk_discounts: vec![],
+        curve: PriceCurve::Flat,
+    });
+    assert!(result.is_err());
+
+    // 零单价
+    let result = execute(deps.as_mut(), env.clone(), owner_info(), ExecuteMsg::SetPricing {
+        extra_prices: vec![Coin { denom: STABLE_DENOM.to_string(), amount: Uint128::zero() }],
+        bulk_discounts: vec![],
+        curve: PriceCurve::Flat,
+    });
+    assert!(result.is_err());
+
+    // 100% 折扣
+    let result = execute(deps.as_mut(), env.clone(), owner_info(), ExecuteMsg::SetPricing {
+        extra_prices: vec![],
+        bulk_discounts: vec![BulkDiscount { min_units: 2, discount_bps: 10_000 }],
+        curve: PriceCurve::Flat,
+    });
+    assert!(result.is_err());
+
+    // 零步长
+    let result = execute(deps.as_mut(), env, owner_info(), ExecuteMsg::SetPricing {
+        extra_prices: vec![],
+        bulk_discounts: vec![],
+        curve: PriceCurve::StepUp { step_units: 0, increase_bps: 100 },
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_set_pricing_unauthorized() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Small, BASE_AMOUNT).unwrap();
+
+    let info = sender(USER1);
+    let result = execute(deps.as_mut(), env, info, ExecuteMsg::SetPricing {
+        extra_prices: vec![],
+        bulk_discounts: vec![],
+        curve: PriceCurve::Flat,
+    });
+    assert_eq!(result.unwrap_err(), ContractError::Unauthorized);
+}
+
+#[test]
+fn test_deposit_with_extra_denom() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Small, BASE_AMOUNT).unwrap();
+    set_pricing(&mut deps, &env, vec![], PriceCurve::Flat);
+
+    let info = MessageInfo { sender: Addr::unchecked(USER1), funds: coins(12, STABLE_DENOM) };
+    let res = execute(deps.as_mut(), env, info, ExecuteMsg::Deposit {}).unwrap();
+    assert_eq!(res.attributes.iter().find(|a| a.key == "minted").unwrap().value, "2");
+    assert_eq!(res.attributes.iter().find(|a| a.key == "denom").unwrap().value, STABLE_DENOM);
+    assert_eq!(res.attributes.iter().find(|a| a.key == "cost").unwrap().value, "10");
+    assert_eq!(res.attributes.iter().find(|a| a.key == "refund").unwrap().value, "2");
+
+    // 本金按币种分别记录，只计实际成交金额
+    let deposit = query_deposit_test(&deps, USER1);
+    assert_eq!(deposit.principal, "0");
+    assert_eq!(deposit.others, vec![Coin { denom: STABLE_DENOM.to_string(), amount: Uint128::from(10u128) }]);
+    assert_eq!(EXTRA_DEPOSITS.load(&deps.storage, (&Addr::unchecked(USER1), STABLE_DENOM)).unwrap(), Uint128::from(10u128));
+}
+
+#[test]
+fn test_deposit_rejects_multiple_coins() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Small, BASE_AMOUNT).unwrap();
+    set_pricing(&mut deps, &env, vec![], PriceCurve::Flat);
+
+    // 同时附带两种可接受币种时整笔拒绝，不会只收取其中一种
+    let funds = vec![Coin { denom: BASE_DENOM.to_string(), amount: Uint128::from(BASE_AMOUNT) }, Coin { denom: STABLE_DENOM.to_string(), amount: Uint128::from(5u128) }];
+    let info = MessageInfo { sender: Addr::unchecked(USER1), funds };
+    let result = execute(deps.as_mut(), env, info, ExecuteMsg::Deposit {});
+    assert_eq!(result.unwrap_err(), ContractError::MultipleCoins { count: 2 });
+    assert_eq!(query_deposit_test(&deps, USER1).principal, "0");
+}
+
+#[test]
+fn test_deposit_bulk_discount() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Small, BASE_AMOUNT).unwrap();
+    set_pricing(&mut deps, &env, vec![BulkDiscount { min_units: 10, discount_bps: 2_000 }], PriceCurve::Flat);
+
+    // 9 个原价需 900，10 个八折只需 800
+    let (msg, info) = create_deposit_msg(BASE_AMOUNT * 8);
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(res.attributes.iter().find(|a| a.key == "minted").unwrap().value, "10");
+    assert_eq!(res.attributes.iter().find(|a| a.key == "cost").unwrap().value, "800");
+}
+
+#[test]
+fn test_deposit_step_up_curve() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Small, BASE_AMOUNT).unwrap();
+    // 每售出 2 个上涨 50%
+    set_pricing(&mut deps, &env, vec![], PriceCurve::StepUp { step_units: 2, increase_bps: 5_000 });
+
+    assert_eq!(quote(&deps, BASE_DENOM, 3).cost.amount, Uint128::from(100u128 + 100 + 150));
+
+    // 350 恰好购买 3 个
+    let (msg, info) = create_deposit_msg(350);
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(res.attributes.iter().find(|a| a.key == "minted").unwrap().value, "3");
+
+    // 下一个单价为 150，不足则拒绝
+    let (msg, info) = create_deposit_msg(149);
+    assert!(execute(deps.as_mut(), env, info, msg).is_err());
+    assert_eq!(quote(&deps, BASE_DENOM, 1).cost.amount, Uint128::from(150u128));
+}
+
+#[test]
+fn test_quote_unknown_denom() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Small, BASE_AMOUNT).unwrap();
+
+    let result = query(deps.as_ref(), env, QueryMsg::Quote { denom: "uatom".to_string(), units: 1 });
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_finalize_pays_out_per_denom() {
+    use dd_blind_box::state::{CONFIG, REVEALS, DEPOSITS, RevealInfo, Payout};
+    use dd_blind_box::state::VoteState;
+
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Small, BASE_AMOUNT).unwrap();
+
+    let user = Addr::unchecked(USER1);
+    DEPOSITS.save(&mut deps.storage, user.clone(), &Payout { principal: Uint128::from(100u128) }).unwrap();
+    EXTRA_DEPOSITS.save(&mut deps.storage, (&user, STABLE_DENOM), &Uint128::from(10u128)).unwrap();
+    REVEALS.save(&mut deps.storage, (0, &user), &RevealInfo { reveal: "r".to_string(), salt: "s".to_string() }).unwrap();
+    let mut config = CONFIG.load(&deps.storage).unwrap();
+    config.vote_state = VoteState::Closed;
+    CONFIG.save(&mut deps.storage, &config).unwrap();
+
+    let (msg, info) = create_finalize_msg();
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    // 单个投票者为一等奖，两个币种均按 2 倍返还
+    let cosmwasm_std::CosmosMsg::Bank(cosmwasm_std::BankMsg::Send { amount, .. }) = &res.messages[0].msg else {
+        panic!("expected bank send");
+    };
+    assert_eq!(amount, &vec![
+        Coin { denom: STABLE_DENOM.to_string(), amount: Uint128::from(20u128) },
+        Coin { denom: BASE_DENOM.to_string(), amount: Uint128::from(200u128) },
+    ]);
+}
+
+fn set_pricing(
+    deps: &mut MockDeps,
+    env: &cosmwasm_std::Env,
+    bulk_discounts: Vec<BulkDiscount>,
+    curve: PriceCurve,
+) {
+    execute(deps.as_mut(), env.clone(), owner_info(), ExecuteMsg::SetPricing {
+        extra_prices: vec![Coin { denom: STABLE_DENOM.to_string(), amount: Uint128::from(5u128) }],
+        bulk_discounts,
+        curve,
+    }).unwrap();
+}
+
+fn quote(deps: &MockDeps, denom: &str, units: u64) -> QuoteResponse {
+    let res = query(deps.as_ref(), cosmwasm_std::testing::mock_env(), QueryMsg::Quote { denom: denom.to_string(), units }).unwrap();
+    cosmwasm_std::from_json(res).unwrap()
+}
@@ -0,0 +1,159 @@
+mod common;
+
+use cosmwasm_std::{Addr, Uint128};
+use dd_blind_box::{
+    contract::{execute, query},
+    error::ContractError,
+    msg::{ExecuteMsg, PrizesResponse, QueryMsg},
+    state::{Scale, VoteState, PRIZE_TOKEN_ID_BASE},
+};
+use luckee_nft::types::NftKind;
+use common::*;
+
+
+#[test]
+fn test_set_tier_prize_success() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let res = execute(deps.as_mut(), env, owner_info(), ExecuteMsg::SetTierPrize {
+        tier: 1,
+        kind: Some(NftKind::Clover),
+        series_id: Some("rare_clover".to_string()),
+    }).unwrap();
+    assert_eq!(res.attributes[0].value, "set_tier_prize");
+    assert_eq!(res.attributes[2].value, "rare_clover");
+
+    let prize = dd_blind_box::state::TIER_PRIZES.load(&deps.storage, 1).unwrap();
+    assert_eq!(prize.kind, NftKind::Clover);
+    assert_eq!(prize.minted, 0);
+}
+
+#[test]
+fn test_set_tier_prize_invalid_tier() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    for tier in [0u8, 4] {
+        let result = execute(deps.as_mut(), env.clone(), owner_info(), ExecuteMsg::SetTierPrize {
+            tier,
+            kind: Some(NftKind::Clover),
+            series_id: None,
+        });
+        assert_eq!(result.unwrap_err(), ContractError::InvalidTier { tier });
+    }
+}
+
+#[test]
+fn test_set_tier_prize_unauthorized() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let info = sender(USER1);
+    let result = execute(deps.as_mut(), env, info, ExecuteMsg::SetTierPrize {
+        tier: 1,
+        kind: Some(NftKind::Clover),
+        series_id: None,
+    });
+    assert_eq!(result.unwrap_err(), ContractError::Unauthorized);
+}
+
+#[test]
+fn test_clear_tier_prize() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    execute(deps.as_mut(), env.clone(), owner_info(), ExecuteMsg::SetTierPrize {
+        tier: 2,
+        kind: Some(NftKind::Clover),
+        series_id: None,
+    }).unwrap();
+    execute(deps.as_mut(), env, owner_info(), ExecuteMsg::SetTierPrize {
+        tier: 2,
+        kind: None,
+        series_id: None,
+    }).unwrap();
+
+    assert!(dd_blind_box::state::TIER_PRIZES.may_load(&deps.storage, 2).unwrap().is_none());
+}
+
+#[test]
+fn test_finalize_mints_tier_prizes() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    execute(deps.as_mut(), env.clone(), owner_info(), ExecuteMsg::SetTierPrize {
+        tier: 1,
+        kind: Some(NftKind::Clover),
+        series_id: Some("rare_clover".to_string()),
+    }).unwrap();
+    setup_closed_environment(&mut deps);
+
+    let (msg, info) = create_finalize_msg();
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(res.attributes.iter().find(|a| a.key == "prizes_minted").unwrap().value, "1");
+
+    // 银行返还消息之外还应有一条批量铸造消息
+    let wasm_msgs = res.messages.iter().filter(|m| matches!(m.msg, cosmwasm_std::CosmosMsg::Wasm(_))).count();
+    assert_eq!(wasm_msgs, 1);
+
+    // 一等奖获得者可以查询到奖品
+    let winner = [USER1, USER2, USER3].into_iter()
+        .find(|u| query_tier_test(&deps, u).tier == 1)
+        .unwrap();
+    let prizes = dd_blind_box::state::PRIZES.load(&deps.storage, Addr::unchecked(winner)).unwrap();
+    assert_eq!(prizes.len(), 1);
+    assert_eq!(prizes[0].token_id, PRIZE_TOKEN_ID_BASE);
+    assert_eq!(prizes[0].tier, 1);
+    assert_eq!(prizes[0].series_id, "rare_clover");
+
+    let prize = dd_blind_box::state::TIER_PRIZES.load(&deps.storage, 1).unwrap();
+    assert_eq!(prize.minted, 1);
+}
+
+#[test]
+fn test_finalize_without_tier_prizes() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    setup_closed_environment(&mut deps);
+
+    let (msg, info) = create_finalize_msg();
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(res.attributes.iter().find(|a| a.key == "prizes_minted").unwrap().value, "0");
+    assert!(res.messages.iter().all(|m| matches!(m.msg, cosmwasm_std::CosmosMsg::Bank(_))));
+}
+
+#[test]
+fn test_prizes_of_empty() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let addr = deps.api.addr_make(USER1);
+    let res = query(deps.as_ref(), env, QueryMsg::PrizesOf { address: addr.to_string() }).unwrap();
+    let prizes: PrizesResponse = cosmwasm_std::from_json(res).unwrap();
+    assert!(prizes.prizes.is_empty());
+}
+
+// 辅助函数：三个已揭示且有本金的用户，阶段为 Closed
+fn setup_closed_environment(deps: &mut MockDeps) {
+    use dd_blind_box::state::{COMMITS, REVEALS, DEPOSITS, CONFIG};
+
+    for (i, user) in [USER1, USER2, USER3].iter().enumerate() {
+        let reveal = format!("vote_{}", i);
+        let salt = format!("salt_{}", i);
+        DEPOSITS.save(&mut deps.storage, Addr::unchecked(*user), &dd_blind_box::state::Payout {
+            principal: Uint128::from(BASE_AMOUNT),
+        }).unwrap();
+        COMMITS.save(&mut deps.storage, (0, &Addr::unchecked(*user)), &dd_blind_box::state::CommitInfo {
+            commitment: calculate_commitment(user, &reveal, &salt),
+        }).unwrap();
+        REVEALS.save(&mut deps.storage, (0, &Addr::unchecked(*user)), &dd_blind_box::state::RevealInfo {
+            reveal,
+            salt,
+        }).unwrap();
+    }
+
+    let mut config = CONFIG.load(&deps.storage).unwrap();
+    config.vote_state = VoteState::Closed;
+    CONFIG.save(&mut deps.storage, &config).unwrap();
+}
@@ -0,0 +1,87 @@
+mod common;
+
+use cosmwasm_std::{coins, Addr, BankMsg, CosmosMsg, Uint128};
+use dd_blind_box::{
+    contract::{execute, query},
+    error::ContractError,
+    msg::{ExecuteMsg, QueryMsg, TreasuryResponse},
+    state::{Scale, VoteState, CONFIG, MAX_PROTOCOL_FEE_BPS},
+};
+use common::*;
+
+
+const COLLECTOR: &str = "cosmwasm17xpfvakm2amg962yls6f84z3kell8c5lc86gn8q4h0dwe4u9jgsqn5wkzf"; // addr_make("fee_collector")
+
+#[test]
+fn test_deposit_charges_protocol_fee() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    set_fee(&mut deps, &env, 500, Some(COLLECTOR), false).unwrap();
+
+    let (msg, info) = create_deposit_msg(BASE_AMOUNT * 2);
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(res.attributes.iter().find(|a| a.key == "fee").unwrap().value, "10");
+    assert_eq!(res.messages[1].msg, CosmosMsg::Bank(BankMsg::Send {
+        to_address: COLLECTOR.to_string(),
+        amount: coins(10, BASE_DENOM),
+    }));
+
+    // 本金按扣费后的净额记录
+    assert_eq!(query_deposit_test(&deps, USER1).principal, "190");
+
+    let res = query(deps.as_ref(), env, QueryMsg::Treasury {}).unwrap();
+    let treasury: TreasuryResponse = cosmwasm_std::from_json(res).unwrap();
+    assert_eq!(treasury.balances[0].fees_collected, Uint128::from(10u128));
+}
+
+#[test]
+fn test_finalize_charges_payout_fee() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    set_fee(&mut deps, &env, 1_000, Some(COLLECTOR), true).unwrap();
+
+    let (msg, info) = create_deposit_msg(BASE_AMOUNT);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    dd_blind_box::state::REVEALS.save(&mut deps.storage, (0, &Addr::unchecked(USER1)), &dd_blind_box::state::RevealInfo {
+        reveal: "r".to_string(),
+        salt: "s".to_string(),
+    }).unwrap();
+    let mut config = CONFIG.load(&deps.storage).unwrap();
+    config.vote_state = VoteState::Closed;
+    CONFIG.save(&mut deps.storage, &config).unwrap();
+
+    let (msg, info) = create_finalize_msg();
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    // 本金 90，一等奖 2 倍返还 180，再扣 10% 协议费
+    assert_eq!(res.messages[0].msg, CosmosMsg::Bank(BankMsg::Send { to_address: USER1.to_string(), amount: coins(162, BASE_DENOM) }));
+    assert_eq!(res.messages[1].msg, CosmosMsg::Bank(BankMsg::Send { to_address: COLLECTOR.to_string(), amount: coins(18, BASE_DENOM) }));
+    assert_eq!(res.attributes.iter().find(|a| a.key == "payout_fee").unwrap().value, "18");
+}
+
+#[test]
+fn test_set_protocol_fee_validation() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    assert!(set_fee(&mut deps, &env, MAX_PROTOCOL_FEE_BPS + 1, Some(COLLECTOR), false).is_err());
+    assert!(set_fee(&mut deps, &env, 100, None, false).is_err());
+    set_fee(&mut deps, &env, 0, None, false).unwrap();
+    assert_eq!(query_config(&deps).protocol_fee.fee_bps, 0);
+
+    let info = sender(USER1);
+    let msg = ExecuteMsg::SetProtocolFee { fee_bps: 100, collector: Some(COLLECTOR.to_string()), on_payouts: false };
+    assert_eq!(execute(deps.as_mut(), env, info, msg).unwrap_err(), ContractError::Unauthorized);
+}
+
+fn set_fee(
+    deps: &mut MockDeps,
+    env: &cosmwasm_std::Env,
+    fee_bps: u16,
+    collector: Option<&str>,
+    on_payouts: bool,
+) -> Result<cosmwasm_std::Response, ContractError> {
+    let info = owner_info();
+    let msg = ExecuteMsg::SetProtocolFee { fee_bps, collector: collector.map(|c| c.to_string()), on_payouts };
+    execute(deps.as_mut(), env.clone(), info, msg)
+}
@@ -0,0 +1,234 @@
+mod common;
+
+use dd_blind_box::{
+    contract::{distribution_hash, execute, query, shuffle_indices},
+    error::ContractError,
+    msg::{ExecuteMsg, QueryMsg, RevealStatusResponse, RevealedItemResponse},
+    state::{HiddenItem, Scale, VoteState, CONFIG},
+};
+use luckee_nft::types::NftKind;
+use common::*;
+
+
+const SALT: &str = "secret_salt";
+
+#[test]
+fn test_commit_distribution_success() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let hash = distribution_hash(&sample_items(10), SALT).unwrap();
+    let res = execute(deps.as_mut(), env.clone(), owner_info(), ExecuteMsg::CommitDistribution { distribution_hash: hash.clone() }).unwrap();
+    assert_eq!(res.attributes[0].value, "commit_distribution");
+
+    let status: RevealStatusResponse = cosmwasm_std::from_json(query(deps.as_ref(), env, QueryMsg::RevealStatus {}).unwrap()).unwrap();
+    assert_eq!(status.distribution_hash, Some(hash));
+    assert_eq!(status.seed, None);
+    assert!(!status.revealed);
+}
+
+#[test]
+fn test_commit_distribution_invalid_hash() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let result = execute(deps.as_mut(), env, owner_info(), ExecuteMsg::CommitDistribution { distribution_hash: "not_a_hash".to_string() });
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_commit_distribution_after_sale_started() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let (msg, info) = create_deposit_msg(BASE_AMOUNT);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let hash = distribution_hash(&sample_items(10), SALT).unwrap();
+    let result = execute(deps.as_mut(), env, owner_info(), ExecuteMsg::CommitDistribution { distribution_hash: hash });
+    assert_eq!(result.unwrap_err(), ContractError::InvalidState);
+}
+
+#[test]
+fn test_reveal_before_seed_fixed() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let items = sample_items(10);
+    let hash = distribution_hash(&items, SALT).unwrap();
+    execute(deps.as_mut(), env.clone(), owner_info(), ExecuteMsg::CommitDistribution { distribution_hash: hash }).unwrap();
+
+    let result = execute(deps.as_mut(), env, owner_info(), reveal_msg(items, SALT));
+    assert_eq!(result.unwrap_err(), ContractError::InvalidState);
+}
+
+#[test]
+fn test_reveal_assigns_shuffled_items() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let items = sample_items(10);
+    let hash = distribution_hash(&items, SALT).unwrap();
+    execute(deps.as_mut(), env.clone(), owner_info(), ExecuteMsg::CommitDistribution { distribution_hash: hash }).unwrap();
+
+    // 售出 3 个
+    let (msg, info) = create_deposit_msg(BASE_AMOUNT * 3);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    close_and_finalize(&mut deps, &env);
+    let status: RevealStatusResponse = cosmwasm_std::from_json(query(deps.as_ref(), env.clone(), QueryMsg::RevealStatus {}).unwrap()).unwrap();
+    let seed = status.seed.expect("seed fixed at finalize");
+
+    execute(deps.as_mut(), env.clone(), owner_info(), reveal_msg(items.clone(), SALT)).unwrap();
+    let res = execute(deps.as_mut(), env.clone(), owner_info(), push_msg(None, None)).unwrap();
+    assert_eq!(res.messages.len(), 3);
+
+    // 任何人都可以用公开的分布与种子复算分配结果
+    let order = shuffle_indices(&seed, items.len());
+    for token_id in 0..3u64 {
+        let revealed: RevealedItemResponse = cosmwasm_std::from_json(query(deps.as_ref(), env.clone(), QueryMsg::RevealedItem { token_id }).unwrap()).unwrap();
+        assert_eq!(revealed.item, Some(items[order[token_id as usize]].clone()));
+    }
+
+    // 未售出的 token 不分配
+    let revealed: RevealedItemResponse = cosmwasm_std::from_json(query(deps.as_ref(), env.clone(), QueryMsg::RevealedItem { token_id: 5 }).unwrap()).unwrap();
+    assert_eq!(revealed.item, None);
+
+    // 不可重复校验或推送
+    let result = execute(deps.as_mut(), env.clone(), owner_info(), reveal_msg(items, SALT));
+    assert_eq!(result.unwrap_err(), ContractError::InvalidState);
+    let result = execute(deps.as_mut(), env, owner_info(), push_msg(None, None));
+    assert_eq!(result.unwrap_err(), ContractError::InvalidState);
+}
+
+#[test]
+fn test_reveal_pages_through_sold_tokens() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let items = sample_items(10);
+    let hash = distribution_hash(&items, SALT).unwrap();
+    execute(deps.as_mut(), env.clone(), owner_info(), ExecuteMsg::CommitDistribution { distribution_hash: hash }).unwrap();
+    let (msg, info) = create_deposit_msg(BASE_AMOUNT * 5);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    close_and_finalize(&mut deps, &env);
+
+    // 推送前须先校验分布
+    let result = execute(deps.as_mut(), env.clone(), owner_info(), push_msg(None, None));
+    assert_eq!(result.unwrap_err(), ContractError::InvalidState);
+    execute(deps.as_mut(), env.clone(), owner_info(), reveal_msg(items, SALT)).unwrap();
+    let status = reveal_status(&deps, &env);
+    assert!(status.verified);
+    assert!(!status.revealed);
+
+    let res = execute(deps.as_mut(), env.clone(), owner_info(), push_msg(None, Some(2))).unwrap();
+    assert_eq!(res.messages.len(), 2);
+    assert!(!reveal_status(&deps, &env).revealed);
+
+    // 起点不可越过已推送的进度
+    let result = execute(deps.as_mut(), env.clone(), owner_info(), push_msg(Some(2), Some(2)));
+    assert!(result.unwrap_err().to_string().contains("continue from token 2"));
+
+    let res = execute(deps.as_mut(), env.clone(), owner_info(), push_msg(Some(1), Some(2))).unwrap();
+    assert_eq!(res.messages.len(), 2);
+    let res = execute(deps.as_mut(), env.clone(), owner_info(), push_msg(Some(3), None)).unwrap();
+    assert_eq!(res.messages.len(), 1);
+
+    let status = reveal_status(&deps, &env);
+    assert!(status.revealed);
+    assert_eq!(status.revealed_count, 5);
+    let result = execute(deps.as_mut(), env, owner_info(), push_msg(Some(4), None));
+    assert_eq!(result.unwrap_err(), ContractError::InvalidState);
+}
+
+#[test]
+fn test_deposit_rejected_after_seed_fixed() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    let hash = distribution_hash(&sample_items(10), SALT).unwrap();
+    execute(deps.as_mut(), env.clone(), owner_info(), ExecuteMsg::CommitDistribution { distribution_hash: hash }).unwrap();
+    let (msg, info) = create_deposit_msg(BASE_AMOUNT);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    close_and_finalize(&mut deps, &env);
+
+    // 种子与分布公开后可推算后续 token 的内容，不再接受购买
+    let (msg, info) = create_deposit_msg(BASE_AMOUNT);
+    assert_eq!(execute(deps.as_mut(), env, info, msg).unwrap_err(), ContractError::RevealSeedFixed);
+}
+
+#[test]
+fn test_reveal_seed_depends_on_reveals() {
+    let seed_for = |reveal: &str, salt: &str| {
+        let (mut deps, env) = setup_test_env();
+        instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+        let hash = distribution_hash(&sample_items(10), SALT).unwrap();
+        execute(deps.as_mut(), env.clone(), owner_info(), ExecuteMsg::CommitDistribution { distribution_hash: hash }).unwrap();
+
+        let (msg, info) = create_deposit_msg(BASE_AMOUNT);
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let (msg, info) = create_commit_msg(calculate_commitment(USER1, reveal, salt));
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let (msg, info) = create_set_vote_state_msg(VoteState::Reveal);
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let (msg, info) = create_reveal_msg(reveal.to_string(), salt.to_string());
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        close_and_finalize(&mut deps, &env);
+        reveal_status(&deps, &env).seed.unwrap()
+    };
+
+    // 同一区块下，揭示值或 salt 不同则种子不同
+    assert_ne!(seed_for("a", "s"), seed_for("b", "s"));
+    assert_ne!(seed_for("a", "s"), seed_for("a", "t"));
+}
+
+#[test]
+fn test_reveal_hash_mismatch() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let items = sample_items(10);
+    let hash = distribution_hash(&items, SALT).unwrap();
+    execute(deps.as_mut(), env.clone(), owner_info(), ExecuteMsg::CommitDistribution { distribution_hash: hash }).unwrap();
+    close_and_finalize(&mut deps, &env);
+
+    let result = execute(deps.as_mut(), env, owner_info(), reveal_msg(items, "other_salt"));
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_shuffle_indices_is_permutation() {
+    let order = shuffle_indices("seed", 100);
+    let mut sorted = order.clone();
+    sorted.sort();
+    assert_eq!(sorted, (0..100).collect::<Vec<_>>());
+    assert_eq!(order, shuffle_indices("seed", 100));
+    assert_ne!(order, shuffle_indices("other_seed", 100));
+}
+
+fn reveal_msg(items: Vec<HiddenItem>, salt: &str) -> ExecuteMsg {
+    ExecuteMsg::RevealDistribution { items, salt: salt.to_string() }
+}
+
+fn push_msg(start_after: Option<u64>, limit: Option<u32>) -> ExecuteMsg {
+    ExecuteMsg::PushRevealedMetadata { start_after, limit }
+}
+
+fn reveal_status(deps: &MockDeps, env: &cosmwasm_std::Env) -> RevealStatusResponse {
+    cosmwasm_std::from_json(query(deps.as_ref(), env.clone(), QueryMsg::RevealStatus {}).unwrap()).unwrap()
+}
+
+fn sample_items(n: usize) -> Vec<HiddenItem> {
+    (0..n).map(|i| HiddenItem {
+        kind: NftKind::Clover,
+        rarity: if i == 0 { "legendary".to_string() } else { format!("common_{}", i) },
+    }).collect()
+}
+
+fn close_and_finalize(deps: &mut MockDeps, env: &cosmwasm_std::Env) {
+    let mut config = CONFIG.load(&deps.storage).unwrap();
+    config.vote_state = VoteState::Closed;
+    CONFIG.save(&mut deps.storage, &config).unwrap();
+
+    let (msg, info) = create_finalize_msg();
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+}
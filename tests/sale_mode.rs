@@ -0,0 +1,109 @@
+mod common;
+
+use cosmwasm_std::{coins, BankMsg, CosmosMsg, Uint128};
+use dd_blind_box::{
+    contract::{execute, query},
+    error::ContractError,
+    msg::{CurrentPriceResponse, ExecuteMsg, QueryMsg},
+    state::{SaleMode, Scale},
+};
+use common::*;
+
+
+#[test]
+fn test_dutch_auction_price_decays_by_height() {
+    let (mut deps, mut env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    let start = env.block.height;
+    set_sale_mode(&mut deps, &env, dutch_auction(start, start + 100)).unwrap();
+
+    assert_eq!(current_price(&deps, &env).price.amount, Uint128::from(200u128));
+
+    // 中点时价格降至一半区间
+    env.block.height = start + 50;
+    assert_eq!(current_price(&deps, &env).price.amount, Uint128::from(125u128));
+
+    // 结束后保持底价
+    env.block.height = start + 500;
+    assert_eq!(current_price(&deps, &env).price.amount, Uint128::from(50u128));
+}
+
+#[test]
+fn test_dutch_auction_refunds_overpayment() {
+    let (mut deps, mut env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    let start = env.block.height;
+    set_sale_mode(&mut deps, &env, dutch_auction(start, start + 100)).unwrap();
+
+    env.block.height = start + 50;
+    let (msg, info) = create_deposit_msg(300);
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(res.attributes.iter().find(|a| a.key == "minted").unwrap().value, "2");
+    assert_eq!(res.attributes.iter().find(|a| a.key == "cost").unwrap().value, "250");
+    assert_eq!(res.messages[1].msg, CosmosMsg::Bank(BankMsg::Send {
+        to_address: USER1.to_string(),
+        amount: coins(50, BASE_DENOM),
+    }));
+
+    // 只记录实际成交金额
+    assert_eq!(query_deposit_test(&deps, USER1).principal, "250");
+    let price = current_price(&deps, &env);
+    assert_eq!(price.last_clearing_price.unwrap().amount, Uint128::from(125u128));
+}
+
+#[test]
+fn test_bonding_curve_prices_by_token_id() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    set_sale_mode(&mut deps, &env, SaleMode::BondingCurve { slope: Uint128::from(10u128) }).unwrap();
+
+    // 100 + 110 + 120 = 330
+    let (msg, info) = create_deposit_msg(350);
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(res.attributes.iter().find(|a| a.key == "minted").unwrap().value, "3");
+    assert_eq!(res.attributes.iter().find(|a| a.key == "refund").unwrap().value, "20");
+
+    let price = current_price(&deps, &env);
+    assert_eq!(price.price.amount, Uint128::from(130u128));
+    assert_eq!(price.last_clearing_price.unwrap().amount, Uint128::from(120u128));
+}
+
+#[test]
+fn test_set_sale_mode_validation() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    // 结束高度早于开始高度
+    assert!(set_sale_mode(&mut deps, &env, dutch_auction(100, 100)).is_err());
+
+    // 底价为零
+    let mode = SaleMode::DutchAuction { start_price: Uint128::from(200u128), floor_price: Uint128::zero(), start_height: 1, end_height: 2 };
+    assert!(set_sale_mode(&mut deps, &env, mode).is_err());
+
+    let info = sender(USER1);
+    let result = execute(deps.as_mut(), env, info, ExecuteMsg::SetSaleMode { mode: SaleMode::Fixed });
+    assert_eq!(result.unwrap_err(), ContractError::Unauthorized);
+}
+
+fn dutch_auction(start_height: u64, end_height: u64) -> SaleMode {
+    SaleMode::DutchAuction {
+        start_price: Uint128::from(200u128),
+        floor_price: Uint128::from(50u128),
+        start_height,
+        end_height,
+    }
+}
+
+fn set_sale_mode(
+    deps: &mut MockDeps,
+    env: &cosmwasm_std::Env,
+    mode: SaleMode,
+) -> Result<cosmwasm_std::Response, ContractError> {
+    let info = owner_info();
+    execute(deps.as_mut(), env.clone(), info, ExecuteMsg::SetSaleMode { mode })
+}
+
+fn current_price(deps: &MockDeps, env: &cosmwasm_std::Env) -> CurrentPriceResponse {
+    let res = query(deps.as_ref(), env.clone(), QueryMsg::CurrentPrice {}).unwrap();
+    cosmwasm_std::from_json(res).unwrap()
+}
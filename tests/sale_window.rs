@@ -0,0 +1,85 @@
+mod common;
+
+use dd_blind_box::{
+    contract::{execute, query},
+    error::ContractError,
+    msg::{ExecuteMsg, QueryMsg, SaleStatusResponse},
+    state::Scale,
+};
+use common::*;
+
+
+#[test]
+fn test_deposit_rejected_outside_sale_window() {
+    let (mut deps, mut env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    let height = env.block.height;
+    set_sale_window(&mut deps, &env, Some(height + 10), Some(height + 20)).unwrap();
+
+    // 尚未开始
+    let (msg, info) = create_deposit_msg(BASE_AMOUNT);
+    let result = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(matches!(result.unwrap_err(), ContractError::OutsideWindow { .. }));
+
+    // 窗口内
+    env.block.height = height + 10;
+    let (msg, info) = create_deposit_msg(BASE_AMOUNT);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // 已结束
+    env.block.height = height + 21;
+    let (msg, info) = create_deposit_msg(BASE_AMOUNT);
+    let result = execute(deps.as_mut(), env, info, msg);
+    assert!(matches!(result.unwrap_err(), ContractError::OutsideWindow { .. }));
+}
+
+#[test]
+fn test_sale_status() {
+    let (mut deps, mut env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let status = sale_status(&deps, &env);
+    assert!(status.open);
+    assert_eq!(status.sold, 0);
+    assert_eq!(status.remaining, 10);
+    assert_eq!(status.ends_at_height, None);
+
+    let (msg, info) = create_deposit_msg(BASE_AMOUNT * 4);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    let height = env.block.height;
+    set_sale_window(&mut deps, &env, None, Some(height + 5)).unwrap();
+
+    let status = sale_status(&deps, &env);
+    assert!(status.open);
+    assert_eq!(status.sold, 4);
+    assert_eq!(status.remaining, 6);
+    assert_eq!(status.ends_at_height, Some(height + 5));
+
+    env.block.height = height + 6;
+    assert!(!sale_status(&deps, &env).open);
+}
+
+#[test]
+fn test_set_sale_window_unauthorized() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let info = sender(USER1);
+    let msg = ExecuteMsg::SetSaleWindow { start_height: None, end_height: Some(1), start_time: None, end_time: None };
+    assert_eq!(execute(deps.as_mut(), env, info, msg).unwrap_err(), ContractError::Unauthorized);
+}
+
+fn set_sale_window(
+    deps: &mut MockDeps,
+    env: &cosmwasm_std::Env,
+    start_height: Option<u64>,
+    end_height: Option<u64>,
+) -> Result<cosmwasm_std::Response, ContractError> {
+    let info = owner_info();
+    execute(deps.as_mut(), env.clone(), info, ExecuteMsg::SetSaleWindow { start_height, end_height, start_time: None, end_time: None })
+}
+
+fn sale_status(deps: &MockDeps, env: &cosmwasm_std::Env) -> SaleStatusResponse {
+    let res = query(deps.as_ref(), env.clone(), QueryMsg::SaleStatus {}).unwrap();
+    cosmwasm_std::from_json(res).unwrap()
+}
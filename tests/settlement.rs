@@ -197,10 +197,10 @@ fn test_finalize_duplicate() {
     let result1 = execute(deps.as_mut(), env.clone(), info, msg);
     assert!(result1.is_ok());
     
-    // 第二次结算（应该成功，但可能没有新的消息）
+    // 同一轮次再次结算被拒绝
     let (msg, info) = create_finalize_msg();
     let result2 = execute(deps.as_mut(), env, info, msg);
-    assert!(result2.is_ok());
+    assert_eq!(result2.unwrap_err(), ContractError::AlreadySettled { round: 0 });
 }
 
 #[test]
@@ -254,6 +254,42 @@ fn test_finalize_tier_ratios() {
     assert!(tier1_count > 0 || tier2_count > 0 || tier3_count > 0);
 }
 
+#[test]
+fn test_new_round_does_not_repay_previous_revealers() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let play_round = |deps: &mut MockDeps, voter: &str, round: u64| {
+        execute(deps.as_mut(), env.clone(), funded(voter, BASE_AMOUNT), ExecuteMsg::Deposit {}).unwrap();
+        let commitment = dd_blind_box::commitment::commitment_v2(&env.block.chain_id, env.contract.address.as_str(), round, voter, "r", "s");
+        execute(deps.as_mut(), env.clone(), sender(voter), ExecuteMsg::CommitVote { commitment }).unwrap();
+        let (msg, info) = create_set_vote_state_msg(VoteState::Reveal);
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        execute(deps.as_mut(), env.clone(), sender(voter), ExecuteMsg::RevealVote { reveal: "r".to_string(), salt: "s".to_string() }).unwrap();
+        let (msg, info) = create_set_vote_state_msg(VoteState::Closed);
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let (msg, info) = create_finalize_msg();
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap()
+    };
+
+    play_round(&mut deps, USER1, 0);
+    assert_eq!(query_tier_test(&deps, USER1).tier, 1);
+
+    // 新一轮清空上一轮的承诺、揭示与分层
+    let (msg, info) = create_set_vote_state_msg(VoteState::Commit);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(query_tier_test(&deps, USER1).tier, 0);
+
+    // 第二轮只向本轮揭示者返还
+    let res = play_round(&mut deps, USER2, 1);
+    let recipients: Vec<String> = res.messages.iter().filter_map(|m| match &m.msg {
+        cosmwasm_std::CosmosMsg::Bank(cosmwasm_std::BankMsg::Send { to_address, .. }) => Some(to_address.clone()),
+        _ => None,
+    }).collect();
+    assert_eq!(recipients, vec![USER2.to_string()]);
+    assert_eq!(query_tier_test(&deps, USER1).tier, 0);
+}
+
 // 辅助函数：设置结算环境
 fn setup_finalize_environment(deps: &mut OwnedDeps<cosmwasm_std::MemoryStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier>, _env: &cosmwasm_std::Env) {
     use dd_blind_box::state::{COMMITS, REVEALS, DEPOSITS, CONFIG};
@@ -278,11 +314,11 @@ fn setup_finalize_environment(deps: &mut OwnedDeps<cosmwasm_std::MemoryStorage,
         let salt = format!("salt_{}", i);
         let commitment = calculate_commitment(user, &reveal, &salt);
         
-        COMMITS.save(&mut deps.storage, Addr::unchecked(*user), &dd_blind_box::state::CommitInfo {
+        COMMITS.save(&mut deps.storage, (0, &Addr::unchecked(*user)), &dd_blind_box::state::CommitInfo {
             commitment,
         }).unwrap();
         
-        REVEALS.save(&mut deps.storage, Addr::unchecked(*user), &dd_blind_box::state::RevealInfo {
+        REVEALS.save(&mut deps.storage, (0, &Addr::unchecked(*user)), &dd_blind_box::state::RevealInfo {
             reveal,
             salt,
         }).unwrap();
@@ -311,11 +347,11 @@ fn setup_voting_environment(deps: &mut OwnedDeps<cosmwasm_std::MemoryStorage, co
     let salt = "salt_1";
     let commitment = calculate_commitment(USER1, reveal, salt);
     
-    COMMITS.save(&mut deps.storage, Addr::unchecked(USER1), &dd_blind_box::state::CommitInfo {
+    COMMITS.save(&mut deps.storage, (0, &Addr::unchecked(USER1)), &dd_blind_box::state::CommitInfo {
         commitment,
     }).unwrap();
     
-    REVEALS.save(&mut deps.storage, Addr::unchecked(USER1), &dd_blind_box::state::RevealInfo {
+    REVEALS.save(&mut deps.storage, (0, &Addr::unchecked(USER1)), &dd_blind_box::state::RevealInfo {
         reveal: reveal.to_string(),
         salt: salt.to_string(),
     }).unwrap();
@@ -340,11 +376,11 @@ fn setup_multiple_users_environment(deps: &mut OwnedDeps<cosmwasm_std::MemorySto
         let salt = format!("salt_{}", i);
         let commitment = calculate_commitment(user, &reveal, &salt);
         
-        COMMITS.save(&mut deps.storage, Addr::unchecked(*user), &dd_blind_box::state::CommitInfo {
+        COMMITS.save(&mut deps.storage, (0, &Addr::unchecked(*user)), &dd_blind_box::state::CommitInfo {
             commitment,
         }).unwrap();
         
-        REVEALS.save(&mut deps.storage, Addr::unchecked(*user), &dd_blind_box::state::RevealInfo {
+        REVEALS.save(&mut deps.storage, (0, &Addr::unchecked(*user)), &dd_blind_box::state::RevealInfo {
             reveal,
             salt,
         }).unwrap();
@@ -378,11 +414,11 @@ fn setup_ten_users_environment(deps: &mut OwnedDeps<cosmwasm_std::MemoryStorage,
         let salt = format!("salt_{}", i);
         let commitment = calculate_commitment(&user, &reveal, &salt);
         
-        COMMITS.save(&mut deps.storage, Addr::unchecked(&user), &dd_blind_box::state::CommitInfo {
+        COMMITS.save(&mut deps.storage, (0, &Addr::unchecked(&user)), &dd_blind_box::state::CommitInfo {
             commitment,
         }).unwrap();
         
-        REVEALS.save(&mut deps.storage, Addr::unchecked(&user), &dd_blind_box::state::RevealInfo {
+        REVEALS.save(&mut deps.storage, (0, &Addr::unchecked(&user)), &dd_blind_box::state::RevealInfo {
             reveal,
             salt,
         }).unwrap();
@@ -0,0 +1,204 @@
+//! 结算基准：在各规模下驱动 `Finalize`，记录存储读写与产生的消息数量
+//!
+//! 默认只运行 10 / 100 人；1k / 10k 较慢，需 `cargo test --test settlement_bench -- --ignored --nocapture`。
+//! 结果与 `tests/fixtures/settlement_bench.json` 比对，任一指标超出基线 10% 即失败；
+//! 缺少基线文件、缺少某个规模的记录或某项指标未记录（为 0）时同样失败。
+//! 设置 `BENCH_UPDATE=1` 运行时改为写入新的基线。
+mod common;
+
+use std::cell::RefCell;
+use std::marker::PhantomData;
+
+use cosmwasm_std::{coins, Addr, MemoryStorage, MessageInfo, Order, OwnedDeps, Record, Storage};
+use dd_blind_box::{
+    contract::execute,
+    msg::ExecuteMsg,
+    state::{Scale, VoteState},
+};
+use serde::{Deserialize, Serialize};
+use common::*;
+
+const BASELINE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/settlement_bench.json");
+/// 相对基线允许的增长（百分比）
+const TOLERANCE_PCT: u64 = 10;
+
+/// 单次结算的存储与消息统计（基线中缺少的指标按 0 读取，视为未记录）
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+struct BenchRecord {
+    voters: u64,
+    outcome: String,
+    reads: u64,
+    range_items: u64,
+    read_bytes: u64,
+    writes: u64,
+    write_bytes: u64,
+    removes: u64,
+    messages: u64,
+    events: u64,
+}
+
+/// 统计读写次数与字节数的存储包装
+struct MeteredStorage {
+    inner: MemoryStorage,
+    meter: RefCell<BenchRecord>,
+}
+
+impl Storage for MeteredStorage {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        let value = self.inner.get(key);
+        let mut meter = self.meter.borrow_mut();
+        meter.reads += 1;
+        meter.read_bytes += value.as_ref().map_or(0, |v| v.len()) as u64;
+        value
+    }
+
+    fn range<'a>(&'a self, start: Option<&[u8]>, end: Option<&[u8]>, order: Order) -> Box<dyn Iterator<Item = Record> + 'a> {
+        Box::new(self.inner.range(start, end, order).inspect(move |(k, v)| {
+            let mut meter = self.meter.borrow_mut();
+            meter.range_items += 1;
+            meter.read_bytes += (k.len() + v.len()) as u64;
+        }))
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        let meter = self.meter.get_mut();
+        meter.writes += 1;
+        meter.write_bytes += (key.len() + value.len()) as u64;
+        self.inner.set(key, value);
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.meter.get_mut().removes += 1;
+        self.inner.remove(key);
+    }
+}
+
+#[test]
+fn bench_settlement() {
+    run(&[(Scale::Tiny, 10), (Scale::Small, 100)]);
+}
+
+#[test]
+#[ignore]
+fn bench_settlement_full() {
+    run(&[(Scale::Tiny, 10), (Scale::Small, 100), (Scale::Medium, 1_000), (Scale::Large, 10_000)]);
+}
+
+fn run(sizes: &[(Scale, u64)]) {
+    let records: Vec<BenchRecord> = sizes.iter().map(|(scale, voters)| measure(scale.clone(), *voters)).collect();
+    println!("{:>7} {:>14} {:>8} {:>8} {:>10} {:>8} {:>10} {:>6} {:>6}", "voters", "outcome", "reads", "ranged", "read_b", "writes", "write_b", "msgs", "events");
+    for r in &records {
+        println!("{:>7} {:>14} {:>8} {:>8} {:>10} {:>8} {:>10} {:>6} {:>6}", r.voters, r.outcome, r.reads, r.range_items, r.read_bytes, r.writes, r.write_bytes, r.messages, r.events);
+    }
+
+    assert_linear(&records);
+
+    let update = std::env::var("BENCH_UPDATE").is_ok();
+    let mut baseline = load_baseline(update);
+    if update {
+        for r in &records {
+            baseline.retain(|b| b.voters != r.voters);
+            baseline.push(r.clone());
+        }
+        baseline.sort_by_key(|b| b.voters);
+        std::fs::write(BASELINE, cosmwasm_std::to_json_vec(&baseline).unwrap()).unwrap();
+        return;
+    }
+    for r in &records {
+        match baseline.iter().find(|b| b.voters == r.voters) {
+            Some(b) => assert_within_baseline(r, b),
+            None => panic!("no baseline for {} voters, run with BENCH_UPDATE=1 to record one", r.voters),
+        }
+    }
+}
+
+/// 准备 voters 个各购买一份、已承诺并揭示的地址，然后在计量存储上执行结算
+fn measure(scale: Scale, voters: u64) -> BenchRecord {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, scale, BASE_AMOUNT).unwrap();
+    let addrs: Vec<Addr> = (0..voters).map(|i| deps.api.addr_make(&format!("voter{}", i))).collect();
+
+    for addr in &addrs {
+        let info = MessageInfo { sender: addr.clone(), funds: coins(BASE_AMOUNT, BASE_DENOM) };
+        execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Deposit {}).unwrap();
+        let info = MessageInfo { sender: addr.clone(), funds: vec![] };
+        let msg = ExecuteMsg::CommitVote { commitment: calculate_commitment(addr.as_str(), "r", "s") };
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    }
+    let (msg, info) = create_set_vote_state_msg(VoteState::Reveal);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    for addr in &addrs {
+        let info = MessageInfo { sender: addr.clone(), funds: vec![] };
+        let msg = ExecuteMsg::RevealVote { reveal: "r".to_string(), salt: "s".to_string() };
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    }
+    let (msg, info) = create_set_vote_state_msg(VoteState::Closed);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let mut metered = OwnedDeps {
+        storage: MeteredStorage { inner: deps.storage, meter: RefCell::new(BenchRecord::default()) },
+        api: deps.api,
+        querier: deps.querier,
+        custom_query_type: PhantomData,
+    };
+    let (msg, info) = create_finalize_msg();
+    let result = execute(metered.as_mut(), env, info, msg);
+
+    let mut record = metered.storage.meter.into_inner();
+    record.voters = voters;
+    match result {
+        Ok(res) => {
+            record.outcome = "ok".to_string();
+            record.messages = res.messages.len() as u64;
+            record.events = res.events.len() as u64;
+        }
+        // 超过 MAX_VOTERS 时结算被拒绝，记录拒绝前的开销
+        Err(err) => record.outcome = format!("{:?}", err).split([' ', '{']).next().unwrap().to_string(),
+    }
+    record
+}
+
+/// 每个投票者的存储开销不应随人数增长（出现超线性增长即失败）
+fn assert_linear(records: &[BenchRecord]) {
+    let ok: Vec<&BenchRecord> = records.iter().filter(|r| r.outcome == "ok").collect();
+    for pair in ok.windows(2) {
+        let (small, large) = (pair[0], pair[1]);
+        for (name, a, b) in metrics(small, large) {
+            // 以 small 的单人开销外推到 large 人数，另加容差
+            let allowed = a * large.voters / small.voters * (100 + TOLERANCE_PCT) / 100;
+            assert!(b <= allowed, "{} grows super-linearly: {} at {} voters vs {} at {} voters", name, a, small.voters, b, large.voters);
+        }
+    }
+}
+
+fn assert_within_baseline(current: &BenchRecord, baseline: &BenchRecord) {
+    assert_eq!(current.outcome, baseline.outcome, "settlement outcome changed at {} voters", current.voters);
+    for (name, base, now) in metrics(baseline, current) {
+        assert!(base > 0 || now == 0, "{} not recorded in baseline at {} voters, run with BENCH_UPDATE=1 to record it", name, current.voters);
+        let allowed = base * (100 + TOLERANCE_PCT) / 100;
+        assert!(now <= allowed, "{} regressed at {} voters: {} -> {} (tolerance {}%)", name, current.voters, base, now, TOLERANCE_PCT);
+    }
+}
+
+fn metrics(a: &BenchRecord, b: &BenchRecord) -> Vec<(&'static str, u64, u64)> {
+    vec![
+        ("reads", a.reads, b.reads),
+        ("range_items", a.range_items, b.range_items),
+        ("read_bytes", a.read_bytes, b.read_bytes),
+        ("writes", a.writes, b.writes),
+        ("write_bytes", a.write_bytes, b.write_bytes),
+        ("removes", a.removes, b.removes),
+        ("messages", a.messages, b.messages),
+        ("events", a.events, b.events),
+    ]
+}
+
+/// 读取基线；仅在写入新基线时允许文件不存在
+fn load_baseline(update: bool) -> Vec<BenchRecord> {
+    match std::fs::read(BASELINE) {
+        Ok(bytes) => cosmwasm_std::from_json(bytes).unwrap(),
+        Err(_) if update => vec![],
+        Err(err) => panic!("cannot read {}: {}, run with BENCH_UPDATE=1 to record a baseline", BASELINE, err),
+    }
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use dd_blind_box::{
+    contract::execute,
+    msg::ExecuteMsg,
+    state::{Scale, VoteState},
+};
+use common::*;
+
+
+#[test]
+fn test_config_exposes_runtime_fields() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let (msg, info) = create_deposit_msg(BASE_AMOUNT * 2);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    let (msg, info) = create_set_paused_msg(true);
+    execute(deps.as_mut(), env, info, msg).unwrap();
+
+    let config = query_config(&deps);
+    assert!(config.paused);
+    assert_eq!(config.next_token_id, 2);
+    assert_eq!(config.commit_window.start_height, None);
+}
+
+#[test]
+fn test_status_counts_and_windows() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let (msg, info) = create_deposit_msg(BASE_AMOUNT * 3);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // 重复承诺只计一次
+    for _ in 0..2 {
+        let (msg, info) = create_commit_msg(calculate_commitment(USER1, "r", "s"));
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    }
+    let info = sender(USER2);
+    execute(deps.as_mut(), env.clone(), info, ExecuteMsg::CommitVote { commitment: calculate_commitment(USER2, "r", "s") }).unwrap();
+
+    let (msg, info) = create_set_vote_state_msg(VoteState::Reveal);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    let (msg, info) = create_reveal_msg("r".to_string(), "s".to_string());
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // 揭示窗口已过
+    let info = owner_info();
+    let msg = ExecuteMsg::SetRevealWindow { start_height: None, end_height: Some(env.block.height - 1), start_time: None, end_time: None };
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let status = query_status(&deps);
+    assert!(!status.paused);
+    assert_eq!(status.vote_state, VoteState::Reveal);
+    assert_eq!(status.minted, 3);
+    assert_eq!(status.remaining, 7);
+    assert_eq!(status.commits, 2);
+    assert_eq!(status.reveals, 1);
+    assert!(!status.settled);
+    assert!(status.commit_window.active);
+    assert!(!status.reveal_window.active);
+    assert_eq!(status.reveal_window.window.end_height, Some(env.block.height - 1));
+}
+
@@ -0,0 +1,109 @@
+mod common;
+
+use cosmwasm_std::{Coin, Uint128};
+use dd_blind_box::{
+    contract::{execute, instantiate},
+    error::ContractError,
+    msg::{ExecuteMsg, InstantiateMsg},
+    state::{Scale, MAX_TOTAL_SUPPLY},
+};
+use common::*;
+
+
+#[test]
+fn test_instantiate_custom_total_supply() {
+    let (mut deps, env) = setup_test_env();
+
+    let res = instantiate(deps.as_mut(), env, owner_info(), instantiate_msg(Some(500))).unwrap();
+    assert_eq!(res.attributes.iter().find(|a| a.key == "total_supply").unwrap().value, "500");
+
+    let config = query_config(&deps);
+    assert_eq!(config.total_supply, 500);
+    // scale 仅作为标签保留
+    assert_eq!(config.scale, Scale::Medium);
+}
+
+#[test]
+fn test_instantiate_total_supply_out_of_range() {
+    for total_supply in [0, MAX_TOTAL_SUPPLY + 1] {
+        let (mut deps, env) = setup_test_env();
+        let result = instantiate(deps.as_mut(), env, owner_info(), instantiate_msg(Some(total_supply)));
+        assert_eq!(result.unwrap_err(), ContractError::InvalidTotalSupply { total_supply, min: 1, max: MAX_TOTAL_SUPPLY });
+    }
+}
+
+#[test]
+fn test_set_total_supply_lowers_supply() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let (msg, info) = create_deposit_msg(BASE_AMOUNT * 3);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    execute(deps.as_mut(), env.clone(), owner_info(), ExecuteMsg::SetTotalSupply { total_supply: 5 }).unwrap();
+    assert_eq!(query_config(&deps).total_supply, 5);
+
+    // 只能售出剩余 2 个
+    let (msg, info) = create_deposit_msg(BASE_AMOUNT * 4);
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(res.attributes.iter().find(|a| a.key == "minted").unwrap().value, "2");
+
+    let (msg, info) = create_deposit_msg(BASE_AMOUNT);
+    assert_eq!(execute(deps.as_mut(), env, info, msg).unwrap_err(), ContractError::NoNftsAvailable);
+}
+
+#[test]
+fn test_set_total_supply_bounds() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let (msg, info) = create_deposit_msg(BASE_AMOUNT * 3);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // 不得低于已铸造数量
+    let result = execute(deps.as_mut(), env.clone(), owner_info(), ExecuteMsg::SetTotalSupply { total_supply: 2 });
+    assert_eq!(result.unwrap_err(), ContractError::InvalidTotalSupply { total_supply: 2, min: 3, max: 10 });
+
+    // 不得上调
+    let result = execute(deps.as_mut(), env, owner_info(), ExecuteMsg::SetTotalSupply { total_supply: 11 });
+    assert_eq!(result.unwrap_err(), ContractError::InvalidTotalSupply { total_supply: 11, min: 3, max: 10 });
+}
+
+#[test]
+fn test_set_total_supply_unauthorized() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let info = sender(USER1);
+    let result = execute(deps.as_mut(), env, info, ExecuteMsg::SetTotalSupply { total_supply: 5 });
+    assert_eq!(result.unwrap_err(), ContractError::Unauthorized);
+}
+
+#[test]
+fn test_close_sale_early() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let (msg, info) = create_deposit_msg(BASE_AMOUNT * 4);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let res = execute(deps.as_mut(), env.clone(), owner_info(), ExecuteMsg::CloseSale {}).unwrap();
+    assert_eq!(res.attributes[0].value, "close_sale");
+    assert_eq!(query_config(&deps).total_supply, 4);
+
+    let (msg, info) = create_deposit_msg(BASE_AMOUNT);
+    assert_eq!(execute(deps.as_mut(), env.clone(), info, msg).unwrap_err(), ContractError::NoNftsAvailable);
+
+    // 已结束的发售不能再次关闭或调整
+    assert_eq!(execute(deps.as_mut(), env.clone(), owner_info(), ExecuteMsg::CloseSale {}).unwrap_err(), ContractError::NoNftsAvailable);
+    assert_eq!(execute(deps.as_mut(), env, owner_info(), ExecuteMsg::SetTotalSupply { total_supply: 4 }).unwrap_err(), ContractError::NoNftsAvailable);
+}
+
+fn instantiate_msg(total_supply: Option<u64>) -> InstantiateMsg {
+    InstantiateMsg {
+        scale: Scale::Medium,
+        base: Coin { denom: BASE_DENOM.to_string(), amount: Uint128::from(BASE_AMOUNT) },
+        first_prize_count: None,
+        total_supply,
+    }
+}
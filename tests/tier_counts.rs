@@ -0,0 +1,84 @@
+mod common;
+
+use cosmwasm_std::Addr;
+use dd_blind_box::{
+    contract::{execute, query},
+    msg::{ExecuteMsg, QueryMsg, TierCountsResponse, TierListResponse},
+    state::{save_tier, Scale, VoteState, TIER_INDEX},
+};
+use common::*;
+
+
+#[test]
+fn test_save_tier_moves_index_entry() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    let user = Addr::unchecked(USER1);
+
+    save_tier(&mut deps.storage, 0, &user, 3).unwrap();
+    save_tier(&mut deps.storage, 0, &Addr::unchecked(USER2), 3).unwrap();
+    // 重新写入不同档位时旧索引被移除，计数随之调整
+    save_tier(&mut deps.storage, 0, &user, 1).unwrap();
+    save_tier(&mut deps.storage, 0, &user, 1).unwrap();
+
+    assert!(!TIER_INDEX.has(&deps.storage, (0, 3, &user)));
+    assert!(TIER_INDEX.has(&deps.storage, (0, 1, &user)));
+    let counts = tier_counts(&deps);
+    assert_eq!(counts, TierCountsResponse { first: 1, second: 0, third: 1 });
+}
+
+#[test]
+fn test_finalize_populates_tier_counts() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    let users = [USER1, USER2, USER3];
+
+    for user in users {
+        let info = funded(user, BASE_AMOUNT);
+        execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Deposit {}).unwrap();
+        let info = sender(user);
+        let commitment = calculate_commitment(user, "r", "s");
+        execute(deps.as_mut(), env.clone(), info, ExecuteMsg::CommitVote { commitment }).unwrap();
+    }
+    let (msg, info) = create_set_vote_state_msg(VoteState::Reveal);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    for user in users {
+        let info = sender(user);
+        let msg = ExecuteMsg::RevealVote { reveal: "r".to_string(), salt: "s".to_string() };
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    }
+    let (msg, info) = create_set_vote_state_msg(VoteState::Closed);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    let (msg, info) = create_finalize_msg();
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let counts = tier_counts(&deps);
+    assert_eq!(counts.first + counts.second + counts.third, users.len() as u64);
+
+    // 各档分页结果与计数一致
+    for (tier, count) in [(1u8, counts.first), (2, counts.second), (3, counts.third)] {
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::TierList { tier, start_after: None, limit: None }).unwrap();
+        let list: TierListResponse = cosmwasm_std::from_json(res).unwrap();
+        assert_eq!(list.addresses.len() as u64, count);
+    }
+}
+
+#[test]
+fn test_tier_list_caps_limit() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    for i in 0..105 {
+        let addr = deps.api.addr_make(&format!("voter{}", i));
+        save_tier(&mut deps.storage, 0, &addr, 1).unwrap();
+    }
+
+    // 超大的 limit 按 MAX_PAGE_LIMIT 截断，不会溢出
+    let list = query_tier_list(&deps, 1, None, Some(u32::MAX));
+    assert_eq!(list.addresses.len(), 100);
+    assert!(list.next_start_after.is_some());
+}
+
+fn tier_counts(deps: &MockDeps) -> TierCountsResponse {
+    let res = query(deps.as_ref(), cosmwasm_std::testing::mock_env(), QueryMsg::TierCounts {}).unwrap();
+    cosmwasm_std::from_json(res).unwrap()
+}
@@ -9,9 +9,9 @@ use dd_blind_box::{
 use common::*;
 
 // Test constants
-const USER1: &str = "user1";
-const USER2: &str = "user2";
-const USER3: &str = "user3";
+const USER1: &str = "cosmwasm1pgzph9rze2j2xxavx4n7pdhxlkgsq7rak245x0vk7mgh3j4le6gqmlwcfu"; // addr_make("user1")
+const USER2: &str = "cosmwasm1vqjarrly327529599rcc4qhzvhwe34pp5uyy4gylvxe5zupeqx3sg08lap"; // addr_make("user2")
+const USER3: &str = "cosmwasm1tps04uptd0rzy2a94jjjx4s0pcmyenvtv7lwfph730muq82f9n9s2w0guk"; // addr_make("user3")
 const BASE_DENOM: &str = "ujunox";
 const BASE_AMOUNT: u128 = 100;
 
@@ -39,6 +39,7 @@ fn instantiate_contract(
             amount: cosmwasm_std::Uint128::from(base_amount),
         },
         first_prize_count: None,
+        total_supply: None,
     };
     dd_blind_box::contract::instantiate(deps.as_mut(), env.clone(), info, msg)
 }
@@ -57,10 +58,8 @@ fn query_tier_list(
 }
 
 fn calculate_commitment(addr: &str, reveal: &str, salt: &str) -> String {
-    use sha2::{Digest, Sha256};
-    let preimage = format!("{}|{}|{}", addr, reveal, salt);
-    let hash = Sha256::digest(preimage.as_bytes());
-    hex::encode(hash)
+    let env = cosmwasm_std::testing::mock_env();
+    dd_blind_box::commitment::commitment_v2(&env.block.chain_id, env.contract.address.as_str(), 0, addr, reveal, salt)
 }
 
 
@@ -209,7 +208,7 @@ fn test_tier_list_default_limit() {
     // 设置一些分层数据
     setup_tier_data(&mut deps);
     
-    // 不指定限制（应该使用默认值50）
+    // 不指定限制（使用默认值30）
     let tier_list = query_tier_list(&deps, 1, None, None);
     assert_eq!(tier_list.addresses.len(), 1);
     assert_eq!(tier_list.next_start_after, None);
@@ -252,22 +251,22 @@ fn test_tier_list_after_finalize() {
 
 // 辅助函数：设置分层数据
 fn setup_tier_data(deps: &mut OwnedDeps<cosmwasm_std::MemoryStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier>) {
-    use dd_blind_box::state::TIERS;
+    use dd_blind_box::state::save_tier;
     
     // 设置不同用户的分层
-    TIERS.save(&mut deps.storage, Addr::unchecked(USER1), &1).unwrap();
-    TIERS.save(&mut deps.storage, Addr::unchecked(USER2), &2).unwrap();
-    TIERS.save(&mut deps.storage, Addr::unchecked(USER3), &3).unwrap();
+    save_tier(&mut deps.storage, 0, &Addr::unchecked(USER1), 1).unwrap();
+    save_tier(&mut deps.storage, 0, &Addr::unchecked(USER2), 2).unwrap();
+    save_tier(&mut deps.storage, 0, &Addr::unchecked(USER3), 3).unwrap();
 }
 
 // 辅助函数：设置多个相同分层的数据
 fn setup_multiple_tier_data(deps: &mut OwnedDeps<cosmwasm_std::MemoryStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier>) {
-    use dd_blind_box::state::TIERS;
+    use dd_blind_box::state::save_tier;
     
     // 设置多个用户为分层1
-    TIERS.save(&mut deps.storage, Addr::unchecked(USER1), &1).unwrap();
-    TIERS.save(&mut deps.storage, Addr::unchecked(USER2), &1).unwrap();
-    TIERS.save(&mut deps.storage, Addr::unchecked(USER3), &1).unwrap();
+    save_tier(&mut deps.storage, 0, &Addr::unchecked(USER1), 1).unwrap();
+    save_tier(&mut deps.storage, 0, &Addr::unchecked(USER2), 1).unwrap();
+    save_tier(&mut deps.storage, 0, &Addr::unchecked(USER3), 1).unwrap();
 }
 
 // 辅助函数：设置完整的投票和结算流程
@@ -295,11 +294,11 @@ fn setup_voting_and_finalize(deps: &mut OwnedDeps<cosmwasm_std::MemoryStorage, c
         let salt = format!("salt_{}", i);
         let commitment = calculate_commitment(user, &reveal, &salt);
         
-        COMMITS.save(&mut deps.storage, Addr::unchecked(*user), &dd_blind_box::state::CommitInfo {
+        COMMITS.save(&mut deps.storage, (0, &Addr::unchecked(*user)), &dd_blind_box::state::CommitInfo {
             commitment,
         }).unwrap();
         
-        REVEALS.save(&mut deps.storage, Addr::unchecked(*user), &dd_blind_box::state::RevealInfo {
+        REVEALS.save(&mut deps.storage, (0, &Addr::unchecked(*user)), &dd_blind_box::state::RevealInfo {
             reveal,
             salt,
         }).unwrap();
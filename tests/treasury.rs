@@ -0,0 +1,162 @@
+mod common;
+
+use cosmwasm_std::{coins, BankMsg, Coin, CosmosMsg, Uint128};
+use dd_blind_box::{
+    contract::{execute, query},
+    error::ContractError,
+    msg::{ExecuteMsg, QueryMsg, RevenueShareMsg, TreasuryResponse},
+    state::{Scale, VoteState, CONFIG},
+};
+use common::*;
+
+
+const DAO: &str = "cosmwasm1p2mnxnnav2efasxu860g4dc6dfttnd09hf50xwgvvrs0pg6pjq0q9383jh"; // addr_make("dao_treasury")
+const CREATOR: &str = "cosmwasm1h34lmpywh4upnjdg90cjf4j70aee6z8qqfspugamjp42e4q28kqs8s7vcp"; // addr_make("creator")
+
+#[test]
+fn test_treasury_reserves_owed_before_settlement() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let (msg, info) = create_deposit_msg(BASE_AMOUNT * 3);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    deps.querier.bank.update_balance(env.contract.address.as_str(), coins(1_000, BASE_DENOM));
+
+    // 结算前按 2 倍本金预留
+    let treasury = treasury(&deps, &env);
+    assert!(!treasury.settled);
+    assert_eq!(treasury.balances[0].owed, Uint128::from(600u128));
+    assert_eq!(treasury.balances[0].withdrawable, Uint128::from(400u128));
+
+    let result = execute(deps.as_mut(), env, owner_info(), withdraw_msg(401, None));
+    assert_eq!(result.unwrap_err(), ContractError::InsufficientProceeds {
+        denom: BASE_DENOM.to_string(),
+        requested: Uint128::from(401u128),
+        available: Uint128::from(400u128),
+    });
+}
+
+#[test]
+fn test_withdraw_applies_revenue_split() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    deps.querier.bank.update_balance(env.contract.address.as_str(), coins(1_000, BASE_DENOM));
+
+    execute(deps.as_mut(), env.clone(), owner_info(), ExecuteMsg::SetRevenueSplit {
+        shares: vec![
+            RevenueShareMsg { address: DAO.to_string(), share_bps: 2_000 },
+            RevenueShareMsg { address: CREATOR.to_string(), share_bps: 500 },
+        ],
+    }).unwrap();
+
+    let res = execute(deps.as_mut(), env.clone(), owner_info(), withdraw_msg(400, Some(USER2))).unwrap();
+    let sends: Vec<(String, Vec<Coin>)> = res.messages.iter().map(|m| match &m.msg {
+        CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => (to_address.clone(), amount.clone()),
+        _ => panic!("expected bank send"),
+    }).collect();
+    assert_eq!(sends, vec![
+        (DAO.to_string(), coins(80, BASE_DENOM)),
+        (CREATOR.to_string(), coins(20, BASE_DENOM)),
+        (USER2.to_string(), coins(300, BASE_DENOM)),
+    ]);
+
+    let treasury = treasury(&deps, &env);
+    assert_eq!(treasury.balances[0].withdrawn, Uint128::from(400u128));
+    assert_eq!(treasury.revenue_split.len(), 2);
+}
+
+#[test]
+fn test_settlement_releases_reserve() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let (msg, info) = create_deposit_msg(BASE_AMOUNT);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    deps.querier.bank.update_balance(env.contract.address.as_str(), coins(BASE_AMOUNT, BASE_DENOM));
+    assert_eq!(treasury(&deps, &env).balances[0].withdrawable, Uint128::zero());
+
+    let (msg, info) = create_commit_msg(calculate_commitment(USER1, "r", "s"));
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    let (msg, info) = create_set_vote_state_msg(VoteState::Reveal);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    let (msg, info) = create_reveal_msg("r".to_string(), "s".to_string());
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    let (msg, info) = create_set_vote_state_msg(VoteState::Closed);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    let (msg, info) = create_finalize_msg();
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let treasury_after = treasury(&deps, &env);
+    assert!(treasury_after.settled);
+    assert_eq!(treasury_after.balances[0].withdrawable, Uint128::from(BASE_AMOUNT));
+
+    // 同一轮次不可再次结算
+    let (msg, info) = create_finalize_msg();
+    assert_eq!(execute(deps.as_mut(), env.clone(), info, msg).unwrap_err(), ContractError::AlreadySettled { round: 0 });
+
+    // 结算后的新充值重新计入应付款
+    let (msg, info) = create_deposit_msg(BASE_AMOUNT);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    deps.querier.bank.update_balance(env.contract.address.as_str(), coins(BASE_AMOUNT * 2, BASE_DENOM));
+    let treasury_after = treasury(&deps, &env);
+    assert!(!treasury_after.settled);
+    assert_eq!(treasury_after.balances[0].owed, Uint128::from(BASE_AMOUNT * 2));
+    assert_eq!(treasury_after.balances[0].withdrawable, Uint128::zero());
+}
+
+#[test]
+fn test_finalize_without_reveals_keeps_reserve() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let (msg, info) = create_deposit_msg(BASE_AMOUNT);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    deps.querier.bank.update_balance(env.contract.address.as_str(), coins(BASE_AMOUNT, BASE_DENOM));
+
+    let mut config = CONFIG.load(&deps.storage).unwrap();
+    config.vote_state = VoteState::Closed;
+    CONFIG.save(&mut deps.storage, &config).unwrap();
+    let (msg, info) = create_finalize_msg();
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(res.attributes.iter().find(|a| a.key == "note").unwrap().value, "no voters");
+
+    // 无人揭示时不结算，本金仍按 2 倍预留
+    let treasury = treasury(&deps, &env);
+    assert!(!treasury.settled);
+    assert_eq!(treasury.balances[0].owed, Uint128::from(BASE_AMOUNT * 2));
+    let result = execute(deps.as_mut(), env, owner_info(), withdraw_msg(1, None));
+    assert!(matches!(result.unwrap_err(), ContractError::InsufficientProceeds { .. }));
+}
+
+#[test]
+fn test_set_revenue_split_validation() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+
+    let over = vec![
+        RevenueShareMsg { address: DAO.to_string(), share_bps: 6_000 },
+        RevenueShareMsg { address: CREATOR.to_string(), share_bps: 5_000 },
+    ];
+    assert!(execute(deps.as_mut(), env.clone(), owner_info(), ExecuteMsg::SetRevenueSplit { shares: over }).is_err());
+
+    let duplicate = vec![
+        RevenueShareMsg { address: DAO.to_string(), share_bps: 100 },
+        RevenueShareMsg { address: DAO.to_string(), share_bps: 100 },
+    ];
+    assert!(execute(deps.as_mut(), env.clone(), owner_info(), ExecuteMsg::SetRevenueSplit { shares: duplicate }).is_err());
+
+    let info = sender(USER1);
+    assert_eq!(execute(deps.as_mut(), env, info, withdraw_msg(1, None)).unwrap_err(), ContractError::Unauthorized);
+}
+
+fn withdraw_msg(amount: u128, recipient: Option<&str>) -> ExecuteMsg {
+    ExecuteMsg::WithdrawProceeds {
+        amount: Coin { denom: BASE_DENOM.to_string(), amount: Uint128::from(amount) },
+        recipient: recipient.map(|r| r.to_string()),
+    }
+}
+
+fn treasury(deps: &MockDeps, env: &cosmwasm_std::Env) -> TreasuryResponse {
+    let res = query(deps.as_ref(), env.clone(), QueryMsg::Treasury {}).unwrap();
+    cosmwasm_std::from_json(res).unwrap()
+}
@@ -0,0 +1,98 @@
+mod common;
+
+use cosmwasm_std::{coins, Addr};
+use dd_blind_box::{
+    contract::{execute, query},
+    msg::{ExecuteMsg, QueryMsg, TokenRange, UserInfoResponse},
+    state::{Scale, VoteState},
+};
+use common::*;
+
+
+#[test]
+fn test_user_info_tracks_purchases_and_votes() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    let user = Addr::unchecked(USER1);
+    let other = Addr::unchecked(USER2);
+
+    execute(deps.as_mut(), env.clone(), funded(user.as_str(), BASE_AMOUNT * 2), ExecuteMsg::Deposit {}).unwrap();
+    execute(deps.as_mut(), env.clone(), funded(other.as_str(), BASE_AMOUNT), ExecuteMsg::Deposit {}).unwrap();
+    execute(deps.as_mut(), env.clone(), funded(user.as_str(), BASE_AMOUNT), ExecuteMsg::Deposit {}).unwrap();
+    commit(&mut deps, &env, &user);
+
+    let info = user_info(&deps, &env, &user);
+    assert_eq!(info.principal, coins(BASE_AMOUNT * 3, BASE_DENOM));
+    assert_eq!(info.units_purchased, 3);
+    assert_eq!(info.purchases, vec![
+        TokenRange { first_token_id: 0, count: 2 },
+        TokenRange { first_token_id: 3, count: 1 },
+    ]);
+    assert!(info.committed);
+    assert!(!info.revealed);
+    assert!(!info.refund_eligible);
+
+    reveal(&mut deps, &env, &user);
+    let info = user_info(&deps, &env, &user);
+    assert!(info.revealed);
+    assert!(info.refund_eligible);
+    assert_eq!(info.tier, None);
+    assert!(info.payout.is_empty());
+}
+
+#[test]
+fn test_user_info_after_settlement() {
+    let (mut deps, env) = setup_test_env();
+    instantiate_contract(&mut deps, &env, Scale::Tiny, BASE_AMOUNT).unwrap();
+    let user = Addr::unchecked(USER1);
+
+    execute(deps.as_mut(), env.clone(), funded(user.as_str(), BASE_AMOUNT), ExecuteMsg::Deposit {}).unwrap();
+    commit(&mut deps, &env, &user);
+    reveal(&mut deps, &env, &user);
+    let (msg, info) = create_set_vote_state_msg(VoteState::Closed);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    let (msg, info) = create_finalize_msg();
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // 唯一揭示者为一等奖，2 倍返还已随结算发出
+    let info = user_info(&deps, &env, &user);
+    assert_eq!(info.tier, Some(1));
+    assert_eq!(info.payout, coins(BASE_AMOUNT * 2, BASE_DENOM));
+    assert!(info.paid_out);
+    assert!(!info.refund_eligible);
+    assert!(info.principal.is_empty());
+
+    // 未参与的地址返回空面板
+    let info = user_info(&deps, &env, &Addr::unchecked(USER3));
+    assert!(info.principal.is_empty());
+    assert_eq!(info.units_purchased, 0);
+    assert!(!info.committed);
+    assert!(!info.paid_out);
+}
+
+fn commit(
+    deps: &mut MockDeps,
+    env: &cosmwasm_std::Env,
+    user: &Addr,
+) {
+    let info = sender(user.as_str());
+    let commitment = calculate_commitment(user.as_str(), "r", "s");
+    execute(deps.as_mut(), env.clone(), info, ExecuteMsg::CommitVote { commitment }).unwrap();
+}
+
+/// 切换到揭示阶段并揭示
+fn reveal(
+    deps: &mut MockDeps,
+    env: &cosmwasm_std::Env,
+    user: &Addr,
+) {
+    let (msg, owner) = create_set_vote_state_msg(VoteState::Reveal);
+    execute(deps.as_mut(), env.clone(), owner, msg).unwrap();
+    let info = sender(user.as_str());
+    execute(deps.as_mut(), env.clone(), info, ExecuteMsg::RevealVote { reveal: "r".to_string(), salt: "s".to_string() }).unwrap();
+}
+
+fn user_info(deps: &MockDeps, env: &cosmwasm_std::Env, address: &Addr) -> UserInfoResponse {
+    let res = query(deps.as_ref(), env.clone(), QueryMsg::UserInfo { address: address.to_string() }).unwrap();
+    cosmwasm_std::from_json(res).unwrap()
+}